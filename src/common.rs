@@ -1,11 +1,22 @@
 use colored::Colorize;
 use std::fmt;
 
+#[cfg(test)]
+use aws_sdk_s3::{
+    config::http::HttpResponse,
+    error::{
+        ErrorMetadata,
+        SdkError,
+    },
+    primitives::SdkBody,
+};
+
 // Emoji used during report output
 pub enum Emoji {
     Arrow,
     Cross,
     Info,
+    Question,
     Tick,
     Warning,
 }
@@ -13,24 +24,64 @@ pub enum Emoji {
 impl fmt::Display for Emoji {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     let symbol = match *self {
-        Self::Arrow   => "❯".yellow(),
-        Self::Cross   => "✖".red(),
-        Self::Info    => "🛈".cyan(),
-        Self::Tick    => "✔".green(),
-        Self::Warning => "⚠️ ".cyan(),
+        Self::Arrow    => "❯".yellow(),
+        Self::Cross    => "✖".red(),
+        Self::Info     => "🛈".cyan(),
+        Self::Question => "?".magenta(),
+        Self::Tick     => "✔".green(),
+        Self::Warning  => "⚠️ ".cyan(),
     };
 
     write!(f, "{}", symbol)
   }
 }
 
-impl From<bool> for Emoji {
-    fn from(item: bool) -> Self {
-        if item {
-            Self::Tick
-        }
-        else {
-            Self::Cross
+// Escape characters that are special in XML and HTML attributes and text
+pub fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&'  => escaped.push_str("&amp;"),
+            '<'  => escaped.push_str("&lt;"),
+            '>'  => escaped.push_str("&gt;"),
+            '"'  => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _    => escaped.push(c),
         }
     }
+
+    escaped
+}
+
+// Builds an S3 service error with the given error code, for testing the
+// handling of API errors.
+#[cfg(test)]
+pub fn service_error<E>(
+    code: &str,
+    generic: fn(ErrorMetadata) -> E,
+) -> SdkError<E, HttpResponse> {
+    let metadata = ErrorMetadata::builder()
+        .code(code)
+        .build();
+
+    let response = HttpResponse::new(
+        404.try_into().expect("valid status code"),
+        SdkBody::empty(),
+    );
+
+    SdkError::service_error(generic(metadata), response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_xml() {
+        let escaped = escape_xml(r#"<"Tom" & 'Jerry'>"#);
+        let expected = "&lt;&quot;Tom&quot; &amp; &apos;Jerry&apos;&gt;";
+
+        assert_eq!(escaped, expected);
+    }
 }
@@ -5,32 +5,137 @@
 #![allow(clippy::option_option)]
 #![allow(clippy::redundant_field_names)]
 #![allow(clippy::uninlined_format_args)]
-use anyhow::Result;
-use clap::Parser;
-use clap::builder::PossibleValuesParser;
+use anyhow::{
+    Context,
+    Result,
+};
+use aws_config::retry::RetryMode;
+use aws_types::region::Region;
+use clap::{
+    ArgGroup,
+    Parser,
+    Subcommand,
+};
+use clap::builder::{
+    PossibleValuesParser,
+    TypedValueParser,
+};
 use colored::control::SHOULD_COLORIZE;
+use std::collections::HashSet;
 use std::env;
+use std::fs;
+use std::io::{
+    self,
+    Write,
+};
+use std::path::{
+    Path,
+    PathBuf,
+};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
 
 mod common;
 mod s3;
 
 use s3::{
+    AccountId,
     Audit,
     Audits,
+    Baseline,
+    BucketFilter,
+    BucketPattern,
+    ClientConfig,
+    Diff,
+    FindingFilter,
+    Mfa,
+    OuFilter,
+    OuId,
+    Progress,
+    Report,
     ReportOptions,
+    ReportOutput,
+    ReportSink,
     ReportType,
+    Reports,
+    SavedReport,
+    Severity,
+    TagPattern,
 };
 
+// Exit codes. Clap exits with 2 on usage errors.
+const EXIT_ERROR: u8 = 1;
+const EXIT_FAILURES: u8 = 3;
+
 #[derive(Debug, Parser)]
-#[command(about, rename_all = "kebab")]
+#[command(about, args_conflicts_with_subcommands = true, rename_all = "kebab")]
+#[command(group(
+    ArgGroup::new("accounts")
+        .args(["account", "account_file", "organization"])
+        .multiple(true),
+))]
 struct CliConfig {
-    /// Specify a specific bucket to audit
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Audit the buckets in this account, by assuming the role given with
+    /// --role-name. May be given multiple times.
+    #[arg(
+        long,
+        requires = "role_name",
+        value_name = "ACCOUNT_ID",
+    )]
+    account: Vec<AccountId>,
+
+    /// Read the IDs of accounts to audit from a file, one per line
+    #[arg(
+        long,
+        requires = "role_name",
+        value_name = "FILE",
+    )]
+    account_file: Option<PathBuf>,
+
+    /// Specify a specific bucket to audit. May be given multiple times.
     #[arg(
         long,
         short,
         value_name = "BUCKET",
     )]
-    bucket: Option<String>,
+    bucket: Vec<String>,
+
+    /// Read the names of buckets to audit from a file, one per line
+    #[arg(
+        long,
+        value_name = "FILE",
+    )]
+    bucket_file: Option<PathBuf>,
+
+    /// Only audit buckets in this region, such as "eu-west-1". May be given
+    /// multiple times.
+    #[arg(
+        long,
+        value_name = "REGION",
+    )]
+    bucket_region: Vec<String>,
+
+    /// Leave out failures found in a previous JSON report, or in a JSON list
+    /// of accepted findings
+    #[arg(
+        long,
+        value_name = "FILE",
+    )]
+    baseline: Option<PathBuf>,
+
+    /// Number of buckets to audit at once. Each bucket's audits run in
+    /// parallel, so up to 7 requests per bucket may be in flight.
+    #[arg(
+        long,
+        default_value = "8",
+        value_name = "N",
+        value_parser = clap::value_parser!(u16).range(1..),
+    )]
+    concurrency: u16,
 
     /// Disable specific audits
     #[arg(
@@ -51,7 +156,7 @@ struct CliConfig {
             "sse",
             "versioning",
             "website",
-        ]),
+        ]).try_map(|s| s.parse::<Audit>()),
     )]
     disable_check: Option<Vec<Audit>>,
 
@@ -74,19 +179,183 @@ struct CliConfig {
             "sse",
             "versioning",
             "website",
-        ]),
+        ]).try_map(|s| s.parse::<Audit>()),
     )]
     enable_check: Option<Vec<Audit>>,
 
-    /// Specify the report output format
+    /// Skip buckets matching this pattern. May be given multiple times.
+    ///
+    /// Patterns are globs, such as "prod-*", or regexes when prefixed with
+    /// "regex:".
+    #[arg(
+        long,
+        value_name = "PATTERN",
+    )]
+    exclude: Vec<BucketPattern>,
+
+    /// Skip accounts in this organizational unit, or any OU below it, with
+    /// --organization. May be given multiple times.
+    #[arg(
+        long,
+        requires = "organization",
+        value_name = "OU_ID",
+    )]
+    exclude_ou: Vec<OuId>,
+
+    /// Skip buckets with this tag, given as KEY=VALUE, or KEY for any value.
+    /// May be given multiple times.
+    #[arg(
+        long,
+        value_name = "KEY[=VALUE]",
+    )]
+    exclude_tag: Vec<TagPattern>,
+
+    /// Send S3 requests to this URL, to audit an S3 compatible store or a
+    /// local emulator
+    #[arg(
+        long,
+        value_name = "URL",
+    )]
+    endpoint_url: Option<String>,
+
+    /// An external ID to give when assuming roles
+    #[arg(
+        long,
+        value_name = "ID",
+    )]
+    external_id: Option<String>,
+
+    /// Exit with a non-zero status if any check at or above this severity
+    /// fails
+    #[arg(
+        long,
+        value_name = "SEVERITY",
+        value_parser = PossibleValuesParser::new(&[
+            "info",
+            "low",
+            "medium",
+            "high",
+            "critical",
+        ]).try_map(|s| s.parse::<Severity>()),
+    )]
+    fail_on: Option<Severity>,
+
+    /// Specify the report output format, optionally followed by a path to
+    /// write it to. May be given multiple times.
+    ///
+    /// Formats: csv, html, json, junit, markdown, ndjson, sarif, text
     #[arg(
         long,
         short,
         default_value = "text",
-        value_name = "FORMAT",
-        value_parser = PossibleValuesParser::new(&["csv", "text"]),
+        value_name = "FORMAT[=PATH]",
+    )]
+    format: Vec<ReportOutput>,
+
+    /// Split the reports up by account, each with its own summary. Reports
+    /// written to a path go to a file per account.
+    #[arg(
+        long,
+        conflicts_with = "group_by_tag",
     )]
-    format: ReportType,
+    group_by_account: bool,
+
+    /// Split the reports up by the value of this tag, each with its own
+    /// summary. Reports written to a path go to a file per tag value.
+    #[arg(
+        long,
+        value_name = "KEY",
+    )]
+    group_by_tag: Option<String>,
+
+    /// Only audit buckets matching this pattern. May be given multiple times.
+    ///
+    /// Patterns are globs, such as "prod-*", or regexes when prefixed with
+    /// "regex:".
+    #[arg(
+        long,
+        value_name = "PATTERN",
+    )]
+    include: Vec<BucketPattern>,
+
+    /// The most attempts to make for each request, including the first.
+    /// Defaults to 3.
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+    )]
+    max_attempts: Option<u32>,
+
+    /// The most S3 API calls to start each second, across all buckets, regions
+    /// and accounts. Retries of a call, and the calls made to assume roles or
+    /// list accounts, aren't counted.
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+    )]
+    max_requests_per_second: Option<u32>,
+
+    /// The serial number, or ARN, of the MFA device protecting the role given
+    /// with --role-arn. The code is read from stdin.
+    #[arg(
+        long,
+        requires = "role_arn",
+        value_name = "SERIAL",
+    )]
+    mfa_serial: Option<String>,
+
+    /// Hide findings below this severity
+    #[arg(
+        long,
+        default_value = "info",
+        value_name = "SEVERITY",
+        value_parser = PossibleValuesParser::new(&[
+            "info",
+            "low",
+            "medium",
+            "high",
+            "critical",
+        ]).try_map(|s| s.parse::<Severity>()),
+    )]
+    min_severity: Severity,
+
+    /// Only show failed checks, and skip buckets without any
+    #[arg(long)]
+    only_failures: bool,
+
+    /// Audit every active account in the AWS Organization, by assuming the
+    /// role given with --role-name. Must be run from the management account
+    /// or a delegated administrator account.
+    #[arg(
+        long,
+        requires = "role_name",
+    )]
+    organization: bool,
+
+    /// Only audit accounts in this organizational unit, or any OU below it,
+    /// with --organization. May be given multiple times.
+    #[arg(
+        long,
+        requires = "organization",
+        value_name = "OU_ID",
+    )]
+    ou: Vec<OuId>,
+
+    /// Write reports to this path, rather than stdout, unless a path was
+    /// given with the format
+    #[arg(
+        long,
+        short,
+        value_name = "PATH",
+    )]
+    output: Option<PathBuf>,
+
+    /// Address buckets by path, as in https://endpoint/bucket, rather than by
+    /// hostname. Often needed with --endpoint-url.
+    #[arg(long)]
+    path_style: bool,
 
     /// Specify an AWS profile name to use
     #[arg(
@@ -95,6 +364,80 @@ struct CliConfig {
         value_name = "NAME",
     )]
     profile: Option<String>,
+
+    /// Region for requests that aren't for a particular bucket, such as
+    /// listing buckets and assuming roles. Defaults to the profile's region,
+    /// or AWS_REGION.
+    #[arg(
+        long,
+        value_name = "REGION",
+    )]
+    region: Option<String>,
+
+    /// How to retry failed and throttled requests. Adaptive also slows down
+    /// requests when throttled.
+    #[arg(
+        long,
+        value_name = "MODE",
+        value_parser = PossibleValuesParser::new(&[
+            "standard",
+            "adaptive",
+        ]).try_map(|s| s.parse::<RetryMode>()),
+    )]
+    retry_mode: Option<RetryMode>,
+
+    /// Assume this role before auditing. With --account, roles in each
+    /// account are assumed from this one.
+    #[arg(
+        long,
+        value_name = "ARN",
+    )]
+    role_arn: Option<String>,
+
+    /// The name of the role to assume in each account given with --account,
+    /// --account-file or --organization
+    #[arg(
+        long,
+        requires = "accounts",
+        value_name = "NAME",
+    )]
+    role_name: Option<String>,
+
+    /// How long assumed role sessions last, in seconds
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(900..=43200),
+    )]
+    session_duration: Option<u64>,
+
+    /// The session name to use when assuming roles, which is shown in
+    /// CloudTrail. Defaults to "s3audit".
+    #[arg(
+        long,
+        value_name = "NAME",
+    )]
+    session_name: Option<String>,
+
+    /// Only audit buckets with this tag, given as KEY=VALUE, or KEY for any
+    /// value. May be given multiple times.
+    #[arg(
+        long,
+        value_name = "KEY[=VALUE]",
+    )]
+    tag: Vec<TagPattern>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Show what changed between two JSON reports
+    Diff {
+        /// The earlier report
+        old: PathBuf,
+
+        /// The later report
+        new: PathBuf,
+    },
 }
 
 // The colored library does a lot of work for us here. It will check various
@@ -118,7 +461,7 @@ fn should_colour_output() {
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     pretty_env_logger::init();
 
     // A few extra checks on top of what colorize itself does.
@@ -126,26 +469,438 @@ async fn main() -> Result<()> {
 
     let cli = CliConfig::parse();
 
-    // Set the AWS_PROFILE environment variable if the user requested a
-    // specific profile.
-    if let Some(profile_name) = cli.profile {
-        env::set_var("AWS_PROFILE", &*profile_name);
+    match run(cli).await {
+        Ok(code) => code,
+        Err(e)   => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::from(EXIT_ERROR)
+        },
+    }
+}
+
+async fn run(cli: CliConfig) -> Result<ExitCode> {
+    match cli.command {
+        Some(Command::Diff { ref old, ref new }) => diff(old, new),
+        None                                     => audit(cli).await,
+    }
+}
+
+// Reads bucket names or account IDs from a file, one per line. Blank lines
+// and lines starting with # are ignored.
+fn read_list_file(path: &Path) -> Result<Vec<String>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Could not read {}", path.display()))?;
+
+    let lines = contents.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(ToString::to_string)
+        .collect();
+
+    Ok(lines)
+}
+
+// Asks for the code from the MFA device on stderr, so that it doesn't end up
+// in the report.
+fn read_mfa_code(serial: &str) -> Result<String> {
+    eprint!("Enter MFA code for {}: ", serial);
+    io::stderr().flush()?;
+
+    let mut code = String::new();
+    io::stdin()
+        .read_line(&mut code)
+        .context("Could not read MFA code")?;
+
+    Ok(code.trim().to_string())
+}
+
+// Audits each account in turn, by assuming the named role in it, passing
+// each report to `on_report`. Accounts that can't be audited are reported,
+// and the rest carry on. Returns true if every account was audited.
+#[allow(clippy::too_many_arguments)]
+async fn report_accounts<F>(
+    client: &s3::Client,
+    accounts: &[AccountId],
+    role_name: &str,
+    buckets: &[String],
+    filter: &BucketFilter,
+    audits: &[Audit],
+    concurrency: usize,
+    on_report: &mut F,
+) -> bool
+where F: FnMut(Report) -> Result<()>,
+{
+    let mut complete = true;
+
+    for account in accounts {
+        let result = match client.for_account(account, role_name).await {
+            Ok(client) => {
+                client
+                    .report(
+                        buckets.to_vec(),
+                        filter,
+                        audits.to_vec(),
+                        concurrency,
+                        on_report,
+                    )
+                    .await
+            },
+            Err(error) => Err(error),
+        };
+
+        if let Err(error) = result {
+            client.progress().suspend(|| {
+                eprintln!("Error: Could not audit account {}: {:#}", account, error);
+            });
+
+            complete = false;
+        }
     }
 
+    complete
+}
+
+// Compares two saved reports, failing if anything regressed
+fn diff(old: &Path, new: &Path) -> Result<ExitCode> {
+    let old = SavedReport::load(old)?;
+    let new = SavedReport::load(new)?;
+
+    let diff = Diff::new(&old, &new);
+
+    let mut writer = io::stdout().lock();
+    diff.text(&mut writer)?;
+    writer.flush()?;
+
+    if diff.regressions() > 0 {
+        return Ok(ExitCode::from(EXIT_FAILURES));
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn audit(cli: CliConfig) -> Result<ExitCode> {
     // Work out which audits we're going to run.
     let audits = Audits::new()
         .disable(cli.disable_check)
         .enable(cli.enable_check)
         .enabled();
 
-    let client = s3::Client::new(None).await;
-    let reports = client.report(cli.bucket, audits).await?;
+    // Check the requested outputs and baseline before we spend time auditing.
+    let report_options = ReportOptions::new(cli.format, cli.output)?;
+    let grouped = cli.group_by_tag.is_some() || cli.group_by_account;
+
+    if grouped {
+        report_options.check_grouping()?;
+    }
+
+    // Accounts given on the command line, in a file and found in the
+    // organization are audited together, each only once.
+    let mut accounts = cli.account;
 
-    let report_options = ReportOptions {
-        output_type: cli.format,
+    if let Some(path) = cli.account_file {
+        for account in read_list_file(&path)? {
+            let account = account.parse()
+                .with_context(|| format!("Invalid account in {}", path.display()))?;
+
+            accounts.push(account);
+        }
+    }
+
+    let baseline = match cli.baseline {
+        None       => Baseline::default(),
+        Some(path) => Baseline::load(&path)?,
+    };
+
+    // Colour codes have no place in files.
+    let text_to_file = report_options.outputs
+        .iter()
+        .any(|output| {
+            matches!(output.output_type, ReportType::Text)
+                && output.path.is_some()
+        });
+
+    if text_to_file {
+        SHOULD_COLORIZE.set_override(false);
+    }
+
+    // Filtering only changes what is shown, not the exit status.
+    let filter = FindingFilter {
+        only_failures: cli.only_failures,
+        min_severity:  cli.min_severity,
     };
 
-    reports.output(&report_options)?;
+    // Buckets given on the command line and in a file are audited together,
+    // each only once.
+    let mut buckets = cli.bucket;
 
-    Ok(())
+    if let Some(path) = cli.bucket_file {
+        buckets.append(&mut read_list_file(&path)?);
+    }
+
+    let mut seen = HashSet::new();
+    buckets.retain(|bucket| seen.insert(bucket.clone()));
+
+    let bucket_regions: Vec<Region> = cli.bucket_region
+        .into_iter()
+        .map(Region::new)
+        .collect();
+
+    // The code is asked for before any requests are made, and only once.
+    let mfa = match cli.mfa_serial {
+        None         => None,
+        Some(serial) => {
+            let token_code = read_mfa_code(&serial)?;
+
+            Some(Mfa {
+                serial,
+                token_code,
+            })
+        },
+    };
+
+    let client_config = ClientConfig {
+        mfa,
+        profile:             cli.profile,
+        region:              cli.region.map(Region::new),
+        role_arn:            cli.role_arn,
+        external_id:         cli.external_id,
+        session_name:        cli.session_name,
+        session_duration:    cli.session_duration.map(Duration::from_secs),
+        endpoint_url:        cli.endpoint_url,
+        force_path_style:    cli.path_style,
+        retry_mode:          cli.retry_mode,
+        max_attempts:        cli.max_attempts,
+        requests_per_second: cli.max_requests_per_second,
+    };
+
+    let bucket_filter = BucketFilter::new(cli.include, cli.exclude)
+        .with_tags(cli.tag, cli.exclude_tag)
+        .with_regions(bucket_regions);
+
+    let progress = Arc::new(Progress::new());
+    let tags = bucket_filter.has_tags() || cli.group_by_tag.is_some();
+
+    let client = s3::Client::new(client_config)
+        .await?
+        .with_progress(Arc::clone(&progress))
+        .with_tags(tags);
+
+    // Accounts in the organization are listed using the credentials we
+    // start with, before any roles in them are assumed.
+    if cli.organization {
+        let ou_filter = OuFilter::new(cli.ou, cli.exclude_ou);
+
+        let mut organization_accounts = client.organization()
+            .accounts(&ou_filter)
+            .await
+            .context("Could not list the accounts in the organization")?;
+
+        accounts.append(&mut organization_accounts);
+    }
+
+    let mut seen = HashSet::new();
+    accounts.retain(|account| seen.insert(account.clone()));
+
+    let concurrency = usize::from(cli.concurrency);
+
+    // The outputs are opened before auditing, and each bucket is written as
+    // soon as it's audited. Grouped reports can only be split up once every
+    // bucket has been audited, so they're kept until then.
+    let mut sinks = if grouped {
+        Vec::new()
+    }
+    else {
+        report_options.open()?
+    };
+
+    sinks.begin()?;
+
+    let fail_on = cli.fail_on;
+    let mut grouped_reports = Vec::new();
+    let mut passed = true;
+    let mut has_errors = false;
+
+    // The baseline and filter are applied to each report as soon as it's
+    // ready, so that it can be written straight away, and the exit status is
+    // worked out as each report arrives.
+    let mut on_report = |mut report: Report| -> Result<()> {
+        report.accepted = baseline.accepted(&report.name);
+        report.filter   = filter;
+
+        passed &= fail_on.map_or(true, |threshold| report.passed(threshold));
+        has_errors |= report.has_errors();
+
+        if grouped {
+            grouped_reports.push(report);
+
+            Ok(())
+        }
+        else {
+            sinks.bucket(&report)
+        }
+    };
+
+    // Without a role, the buckets in the current account are audited. An
+    // account file or organization with no accounts has nothing to audit.
+    let result = match cli.role_name {
+        Some(role_name) => {
+            let complete = report_accounts(
+                &client,
+                &accounts,
+                &role_name,
+                &buckets,
+                &bucket_filter,
+                &audits,
+                concurrency,
+                &mut on_report,
+            ).await;
+
+            Ok(complete)
+        },
+        None => {
+            client
+                .report(
+                    buckets,
+                    &bucket_filter,
+                    audits,
+                    concurrency,
+                    &mut on_report,
+                )
+                .await
+                .map(|()| true)
+        },
+    };
+
+    // The progress line is cleared before any errors or reports are written
+    progress.finish();
+
+    let complete = result?;
+    let has_errors = has_errors || !complete;
+
+    let mut reports = Reports::new(grouped_reports);
+    reports.sort();
+
+    if let Some(tag) = cli.group_by_tag {
+        for group in reports.group_by_tag(&tag) {
+            group.output(&report_options)?;
+        }
+    }
+    else if cli.group_by_account {
+        for group in reports.group_by_account() {
+            group.output(&report_options)?;
+        }
+    }
+    else {
+        sinks.end()?;
+    }
+
+    // Fail if the user asked us to and any checks failed at or above the
+    // requested severity.
+    if !passed {
+        return Ok(ExitCode::from(EXIT_FAILURES));
+    }
+
+    // The report is incomplete if any checks or accounts couldn't be
+    // evaluated.
+    if has_errors {
+        eprintln!("Error: Some checks could not be evaluated");
+        return Ok(ExitCode::from(EXIT_ERROR));
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_format() {
+        let cli = CliConfig::try_parse_from([
+            "s3audit",
+            "--format",
+            "json",
+        ]);
+
+        assert!(cli.is_ok());
+        assert!(CliConfig::try_parse_from(["s3audit", "--format", "yaml"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_fail_on() {
+        let cli = CliConfig::try_parse_from([
+            "s3audit",
+            "--fail-on",
+            "high",
+        ]).unwrap();
+
+        assert_eq!(cli.fail_on, Some(Severity::High));
+        assert!(CliConfig::try_parse_from(["s3audit", "--fail-on", "urgent"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_organization() {
+        let cli = CliConfig::try_parse_from([
+            "s3audit",
+            "--organization",
+            "--role-name",
+            "OrganizationAccountAccessRole",
+            "--ou",
+            "ou-ab12-cd34ef56",
+            "--exclude-ou",
+            "ou-ab12-ab12ab12",
+        ]).unwrap();
+
+        assert!(cli.organization);
+        assert_eq!(cli.ou, vec!["ou-ab12-cd34ef56".parse().unwrap()]);
+        assert_eq!(cli.exclude_ou, vec!["ou-ab12-ab12ab12".parse().unwrap()]);
+
+        // A role is needed to audit the accounts, and OUs only filter the
+        // organization's accounts
+        assert!(CliConfig::try_parse_from(["s3audit", "--organization"]).is_err());
+        assert!(CliConfig::try_parse_from(["s3audit", "--ou", "ou-ab12-cd34ef56"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_role_name() {
+        let cli = CliConfig::try_parse_from([
+            "s3audit",
+            "--account",
+            "123456789012",
+            "--role-name",
+            "AuditRole",
+        ]).unwrap();
+
+        assert_eq!(cli.role_name.as_deref(), Some("AuditRole"));
+
+        // The role is only assumed in the accounts given, so it's a usage
+        // error without any
+        let err = CliConfig::try_parse_from(["s3audit", "--role-name", "AuditRole"]).unwrap_err();
+        assert_eq!(err.exit_code(), 2);
+    }
+
+    #[test]
+    fn test_parse_regions() {
+        // The bucket regions don't affect the region requests are sent to
+        let cli = CliConfig::try_parse_from([
+            "s3audit",
+            "--bucket-region",
+            "eu-west-1",
+            "--bucket-region",
+            "us-east-1",
+        ]).unwrap();
+
+        assert_eq!(cli.bucket_region, vec!["eu-west-1", "us-east-1"]);
+        assert_eq!(cli.region, None);
+
+        let cli = CliConfig::try_parse_from([
+            "s3audit",
+            "--region",
+            "eu-west-2",
+        ]).unwrap();
+
+        assert_eq!(cli.region, Some("eu-west-2".into()));
+        assert!(cli.bucket_region.is_empty());
+    }
 }
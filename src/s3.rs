@@ -1,22 +1,48 @@
 // Imports all S3 types
+mod account;
 mod acl;
 mod audits;
+mod baseline;
+mod bucket_filter;
+mod checks;
 mod client;
+mod client_config;
+mod diff;
 mod encryption;
+mod finding;
 mod logging;
+mod organization;
 mod policy;
+mod progress;
 mod public_access_block;
 mod report;
+mod retry;
+mod saved_report;
+mod severity;
+mod tags;
 mod versioning;
 mod website;
 
+pub use account::*;
 pub use acl::*;
 pub use audits::*;
+pub use baseline::*;
+pub use bucket_filter::*;
+pub use checks::*;
 pub use client::*;
+pub use client_config::*;
+pub use diff::*;
 pub use encryption::*;
+pub use finding::*;
 pub use logging::*;
+pub use organization::*;
 pub use policy::*;
+pub use progress::*;
 pub use public_access_block::*;
 pub use report::*;
+pub use retry::*;
+pub use saved_report::*;
+pub use severity::*;
+pub use tags::*;
 pub use versioning::*;
 pub use website::*;
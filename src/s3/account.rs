@@ -0,0 +1,65 @@
+// AWS accounts audited by assuming a role in each of them
+use anyhow::{
+    anyhow,
+    Error,
+};
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+// Account IDs are always 12 digits, leading zeros included
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(transparent)]
+pub struct AccountId(String);
+
+impl AccountId {
+    // The ARN of the named role in this account
+    pub fn role_arn(&self, role_name: &str) -> String {
+        format!("arn:aws:iam::{}:role/{}", self.0, role_name)
+    }
+}
+
+impl fmt::Display for AccountId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for AccountId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let valid = s.len() == 12 && s.chars().all(|c| c.is_ascii_digit());
+
+        if !valid {
+            return Err(anyhow!("Account IDs must be 12 digits: {}", s));
+        }
+
+        Ok(Self(s.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_from_str() {
+        let account: AccountId = "012345678901".parse().unwrap();
+
+        assert_eq!(account.to_string(), "012345678901");
+        assert!("12345".parse::<AccountId>().is_err());
+        assert!("01234567890a".parse::<AccountId>().is_err());
+    }
+
+    #[test]
+    fn test_role_arn() {
+        let account: AccountId = "012345678901".parse().unwrap();
+
+        assert_eq!(
+            account.role_arn("s3audit"),
+            "arn:aws:iam::012345678901:role/s3audit",
+        );
+    }
+}
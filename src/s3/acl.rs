@@ -1,7 +1,11 @@
 // Bucket ACL
-use crate::common::Emoji;
+use crate::s3::{
+    Finding,
+    Findings,
+    ACL_PUBLIC_ACCESS,
+};
 use aws_sdk_s3::operation::get_bucket_acl::GetBucketAclOutput;
-use std::fmt;
+use serde::Serialize;
 
 // Grantee URIs that indicate public access
 const PUBLIC_URIS: &[&str] = &[
@@ -9,7 +13,8 @@ const PUBLIC_URIS: &[&str] = &[
     "http://acs.amazonaws.com/groups/global/AuthenticatedUsers",
 ];
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum BucketAcl {
     Private,
     Public,
@@ -43,21 +48,25 @@ impl From<GetBucketAclOutput> for BucketAcl {
     }
 }
 
-impl fmt::Display for BucketAcl {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let output = match self {
+impl Findings for BucketAcl {
+    fn findings(&self) -> Vec<Finding> {
+        let finding = match self {
             Self::Private => {
-                let emoji = Emoji::Tick;
-                format!("{} Bucket ACL doesn't allow access to 'Everyone' or \
-                         'Any authenticated AWS user'", emoji)
+                Finding::pass(
+                    &ACL_PUBLIC_ACCESS,
+                    "Bucket ACL doesn't allow access to 'Everyone' or \
+                     'Any authenticated AWS user'",
+                )
             },
             Self::Public => {
-                let emoji = Emoji::Warning;
-                format!("{} Bucket allows public access via ACL", emoji)
-            }
+                Finding::fail(
+                    &ACL_PUBLIC_ACCESS,
+                    "Bucket allows public access via ACL",
+                )
+            },
         };
 
-        write!(f, "{}", output)
+        vec![finding]
     }
 }
 
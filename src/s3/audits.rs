@@ -3,7 +3,9 @@ use anyhow::{
     anyhow,
     Error,
 };
+use serde::Serialize;
 use std::collections::HashSet;
+use std::fmt;
 use std::str::FromStr;
 
 // Quickly create a HashSet, in the style of a vec![]
@@ -21,9 +23,11 @@ macro_rules! hashset {
     };
 }
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum Audit {
     Acl,
+    #[default]
     All,
     Cloudfront,
     Logging,
@@ -35,6 +39,26 @@ pub enum Audit {
     Website,
 }
 
+// Displays the canonical name of the audit, as accepted by FromStr
+impl fmt::Display for Audit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Acl                  => "acl",
+            Self::All                  => "all",
+            Self::Cloudfront           => "cloudfront",
+            Self::Logging              => "logging",
+            Self::MfaDelete            => "mfa-delete",
+            Self::Policy               => "policy",
+            Self::PublicAccessBlocks   => "public-access-blocks",
+            Self::ServerSideEncryption => "server-side-encryption",
+            Self::Versioning           => "versioning",
+            Self::Website              => "website",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
 impl FromStr for Audit {
     type Err = Error;
 
@@ -66,12 +90,6 @@ impl FromStr for Audit {
     }
 }
 
-impl Default for Audit {
-    fn default() -> Self {
-        Self::All
-    }
-}
-
 #[derive(Clone, Debug)]
 pub struct Audits(HashSet<Audit>);
 
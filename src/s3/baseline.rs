@@ -0,0 +1,225 @@
+// Baselines of known failures, which are left out of reports
+use anyhow::{
+    Context,
+    Result,
+};
+use crate::s3::{
+    Finding,
+    SavedReport,
+};
+use serde::Deserialize;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use std::fs;
+use std::path::Path;
+
+// An explicitly accepted failure. Without a message, every failure of the
+// check on the bucket is accepted.
+#[derive(Debug, Deserialize)]
+pub struct AcceptedFinding {
+    pub bucket:  String,
+    pub check:   String,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+// The failures accepted on a bucket, as check IDs and messages. Some checks
+// have a finding for each problem, such as each policy statement, so
+// accepting one of them mustn't hide new ones.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AcceptedFindings(HashSet<(String, Option<String>)>);
+
+impl AcceptedFindings {
+    pub fn insert(&mut self, check: &str, message: Option<&str>) {
+        self.0.insert((check.into(), message.map(ToString::to_string)));
+    }
+
+    // Returns true if the finding's check was accepted without a message, or
+    // with the finding's message
+    pub fn contains(&self, finding: &Finding) -> bool {
+        let check = finding.check_id().to_string();
+
+        self.0.contains(&(check.clone(), None))
+            || self.0.contains(&(check, Some(finding.message().into())))
+    }
+}
+
+// A baseline is either a previous JSON report, in which case all of its
+// failures are accepted, or an explicit list of accepted failures.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BaselineFile {
+    Report(SavedReport),
+    Accepted(Vec<AcceptedFinding>),
+}
+
+// Accepted findings, keyed by bucket name
+#[derive(Debug, Default)]
+pub struct Baseline(HashMap<String, AcceptedFindings>);
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+
+        Self::parse(&contents)
+            .with_context(|| {
+                format!(
+                    "Could not parse {} as a JSON report or a list of accepted findings",
+                    path.display(),
+                )
+            })
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let file: BaselineFile = serde_json::from_str(contents)?;
+
+        let accepted = match file {
+            BaselineFile::Accepted(accepted) => accepted,
+            BaselineFile::Report(report)     => {
+                report.buckets
+                    .into_iter()
+                    .flat_map(|bucket| {
+                        let name = bucket.name;
+
+                        bucket.findings
+                            .into_iter()
+                            .filter(|finding| finding.status.is_failure())
+                            .map(move |finding| {
+                                AcceptedFinding {
+                                    bucket:  name.clone(),
+                                    check:   finding.check,
+                                    message: Some(finding.message),
+                                }
+                            })
+                    })
+                    .collect()
+            },
+        };
+
+        let mut baseline: HashMap<String, AcceptedFindings> = HashMap::new();
+
+        for finding in accepted {
+            baseline.entry(finding.bucket)
+                .or_default()
+                .insert(&finding.check, finding.message.as_deref());
+        }
+
+        Ok(Self(baseline))
+    }
+
+    // Returns the accepted findings for the given bucket
+    pub fn accepted(&self, bucket: &str) -> AcceptedFindings {
+        self.0.get(bucket)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::POLICY_WILDCARDS;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_report() {
+        let contents = r#"{
+            "buckets": [
+                {
+                    "name": "example-bucket",
+                    "findings": [
+                        {
+                            "check": "s3audit/acl-public-access",
+                            "audit": "acl",
+                            "status": "pass",
+                            "severity": "critical",
+                            "message": "Bucket ACL doesn't allow access to 'Everyone' or 'Any authenticated AWS user'",
+                            "remediation": ""
+                        },
+                        {
+                            "check": "s3audit/logging",
+                            "audit": "logging",
+                            "status": "fail",
+                            "severity": "medium",
+                            "message": "Server access logging is disabled",
+                            "remediation": ""
+                        }
+                    ]
+                }
+            ],
+            "summary": {}
+        }"#;
+
+        let baseline = Baseline::parse(contents).unwrap();
+
+        let mut expected = AcceptedFindings::default();
+        expected.insert("s3audit/logging", Some("Server access logging is disabled"));
+
+        assert_eq!(baseline.accepted("example-bucket"), expected);
+        assert_eq!(baseline.accepted("other-bucket"), AcceptedFindings::default());
+    }
+
+    #[test]
+    fn test_parse_accepted() {
+        let contents = r#"[
+            {"bucket": "example-bucket", "check": "s3audit/website"}
+        ]"#;
+
+        let baseline = Baseline::parse(contents).unwrap();
+
+        let mut expected = AcceptedFindings::default();
+        expected.insert("s3audit/website", None);
+
+        assert_eq!(baseline.accepted("example-bucket"), expected);
+    }
+
+    #[test]
+    fn test_accepted_statement() {
+        let contents = r#"[
+            {
+                "bucket": "example-bucket",
+                "check": "s3audit/policy-wildcards",
+                "message": "Statement \"Old\" allows wildcard Action \"s3:*\""
+            }
+        ]"#;
+
+        let accepted = Baseline::parse(contents)
+            .unwrap()
+            .accepted("example-bucket");
+
+        let old = Finding::fail(
+            &POLICY_WILDCARDS,
+            r#"Statement "Old" allows wildcard Action "s3:*""#,
+        );
+
+        let new = Finding::fail(
+            &POLICY_WILDCARDS,
+            r#"Statement "New" allows wildcard Action "s3:*""#,
+        );
+
+        // Accepting one statement doesn't hide another added later
+        assert!(accepted.contains(&old));
+        assert!(!accepted.contains(&new));
+    }
+
+    #[test]
+    fn test_accepted_check() {
+        let mut accepted = AcceptedFindings::default();
+        accepted.insert("s3audit/policy-wildcards", None);
+
+        let finding = Finding::fail(
+            &POLICY_WILDCARDS,
+            r#"Statement "New" allows wildcard Action "s3:*""#,
+        );
+
+        assert!(accepted.contains(&finding));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Baseline::parse(r#"{"something": "else"}"#).is_err());
+    }
+}
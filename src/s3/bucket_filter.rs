@@ -0,0 +1,298 @@
+// Filters for choosing which buckets to audit
+use anyhow::{
+    anyhow,
+    Error,
+    Result,
+};
+use aws_types::region::Region;
+use crate::s3::BucketTags;
+use regex::Regex;
+use std::fmt;
+use std::str::FromStr;
+
+// Patterns are globs unless prefixed with "regex:"
+const REGEX_PREFIX: &str = "regex:";
+
+#[derive(Clone, Debug)]
+pub struct BucketPattern {
+    pattern: String,
+    regex:   Regex,
+}
+
+impl BucketPattern {
+    pub fn is_match(&self, name: &str) -> bool {
+        self.regex.is_match(name)
+    }
+}
+
+impl fmt::Display for BucketPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.pattern)
+    }
+}
+
+impl FromStr for BucketPattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let regex = match s.strip_prefix(REGEX_PREFIX) {
+            Some(regex) => Regex::new(regex)?,
+            None        => Regex::new(&glob_to_regex(s))?,
+        };
+
+        Ok(Self {
+            regex,
+            pattern: s.into(),
+        })
+    }
+}
+
+// Converts a glob into an anchored regex. `*` matches any number of
+// characters, `?` matches a single character and `[...]` matches a character
+// class. Everything else matches literally.
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut in_class = false;
+
+    for c in glob.chars() {
+        match c {
+            '*' if !in_class => regex.push_str(".*"),
+            '?' if !in_class => regex.push('.'),
+            '[' if !in_class => {
+                in_class = true;
+                regex.push('[');
+            },
+            ']' if in_class => {
+                in_class = false;
+                regex.push(']');
+            },
+            '!' if in_class && regex.ends_with('[') => regex.push('^'),
+            c if in_class => regex.push(c),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex.push('$');
+    regex
+}
+
+// A tag to match, as "KEY=VALUE", or "KEY" to match any value
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TagPattern {
+    key:   String,
+    value: Option<String>,
+}
+
+impl TagPattern {
+    pub fn is_match(&self, tags: &BucketTags) -> bool {
+        match (tags.get(&self.key), &self.value) {
+            (Some(value), Some(expected)) => value == expected,
+            (Some(_), None)               => true,
+            (None, _)                     => false,
+        }
+    }
+}
+
+impl FromStr for TagPattern {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = match s.split_once('=') {
+            None               => (s, None),
+            Some((key, value)) => (key, Some(value.into())),
+        };
+
+        if key.is_empty() {
+            return Err(anyhow!("Empty tag key"));
+        }
+
+        Ok(Self {
+            value,
+            key: key.into(),
+        })
+    }
+}
+
+// A bucket is audited if it matches any include pattern, or there are none,
+// and doesn't match any exclude pattern. Tags and regions work the same way,
+// but can only be checked once they've been looked up.
+#[derive(Debug, Default)]
+pub struct BucketFilter {
+    include:      Vec<BucketPattern>,
+    exclude:      Vec<BucketPattern>,
+    include_tags: Vec<TagPattern>,
+    exclude_tags: Vec<TagPattern>,
+    regions:      Vec<Region>,
+}
+
+impl BucketFilter {
+    pub fn new(include: Vec<BucketPattern>, exclude: Vec<BucketPattern>) -> Self {
+        Self {
+            include,
+            exclude,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_tags(
+        mut self,
+        include_tags: Vec<TagPattern>,
+        exclude_tags: Vec<TagPattern>,
+    ) -> Self {
+        self.include_tags = include_tags;
+        self.exclude_tags = exclude_tags;
+        self
+    }
+
+    pub fn with_regions(mut self, regions: Vec<Region>) -> Self {
+        self.regions = regions;
+        self
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        let included = self.include.is_empty()
+            || self.include.iter().any(|pattern| pattern.is_match(name));
+
+        let excluded = self.exclude.iter()
+            .any(|pattern| pattern.is_match(name));
+
+        included && !excluded
+    }
+
+    pub fn matches_tags(&self, tags: &BucketTags) -> bool {
+        let included = self.include_tags.is_empty()
+            || self.include_tags.iter().any(|pattern| pattern.is_match(tags));
+
+        let excluded = self.exclude_tags.iter()
+            .any(|pattern| pattern.is_match(tags));
+
+        included && !excluded
+    }
+
+    // Tags only need to be looked up if they're being filtered on
+    pub fn has_tags(&self) -> bool {
+        !self.include_tags.is_empty() || !self.exclude_tags.is_empty()
+    }
+
+    // Buckets whose region couldn't be found only match if no regions were
+    // given, as we can't tell where they are.
+    pub fn matches_region(&self, region: Option<&Region>) -> bool {
+        self.regions.is_empty()
+            || region.is_some_and(|region| self.regions.contains(region))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn pattern(s: &str) -> BucketPattern {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_glob_to_regex() {
+        let tests = vec![
+            ("prod-*",          "^prod\\-.*$"),
+            ("logs.?",          "^logs\\..$"),
+            ("team-[ab]-*",     "^team\\-[ab]\\-.*$"),
+            ("team-[!ab]-data", "^team\\-[^ab]\\-data$"),
+        ];
+
+        for (glob, expected) in tests {
+            assert_eq!(glob_to_regex(glob), expected);
+        }
+    }
+
+    #[test]
+    fn test_pattern_glob() {
+        let pattern = pattern("prod-*");
+
+        assert!(pattern.is_match("prod-data"));
+        assert!(!pattern.is_match("preprod-data"));
+        assert!(!pattern.is_match("staging-data"));
+    }
+
+    #[test]
+    fn test_pattern_regex() {
+        let pattern = pattern("regex:^prod-[0-9]+$");
+
+        assert!(pattern.is_match("prod-42"));
+        assert!(!pattern.is_match("prod-data"));
+    }
+
+    #[test]
+    fn test_pattern_invalid_regex() {
+        assert!("regex:prod-(".parse::<BucketPattern>().is_err());
+    }
+
+    #[test]
+    fn test_filter() {
+        let filter = BucketFilter::new(
+            vec![pattern("prod-*"), pattern("shared-*")],
+            vec![pattern("*-logs")],
+        );
+
+        assert!(filter.matches("prod-data"));
+        assert!(filter.matches("shared-assets"));
+        assert!(!filter.matches("prod-logs"));
+        assert!(!filter.matches("staging-data"));
+    }
+
+    #[test]
+    fn test_filter_default() {
+        let filter = BucketFilter::default();
+
+        assert!(filter.matches("anything"));
+        assert!(filter.matches_tags(&BucketTags::default()));
+        assert!(filter.matches_region(None));
+        assert!(!filter.has_tags());
+    }
+
+    #[test]
+    fn test_tag_pattern() {
+        let pattern: TagPattern = "owner=team-a".parse().unwrap();
+
+        assert!(pattern.is_match(&BucketTags::from([("owner", "team-a")])));
+        assert!(!pattern.is_match(&BucketTags::from([("owner", "team-b")])));
+        assert!(!pattern.is_match(&BucketTags::default()));
+
+        let pattern: TagPattern = "owner".parse().unwrap();
+
+        assert!(pattern.is_match(&BucketTags::from([("owner", "team-b")])));
+        assert!("=team-a".parse::<TagPattern>().is_err());
+    }
+
+    #[test]
+    fn test_filter_tags() {
+        let filter = BucketFilter::default()
+            .with_tags(
+                vec!["owner=team-a".parse().unwrap()],
+                vec!["s3audit:ignore=true".parse().unwrap()],
+            );
+
+        let ignored = BucketTags::from([
+            ("owner", "team-a"),
+            ("s3audit:ignore", "true"),
+        ]);
+
+        assert!(filter.matches_tags(&BucketTags::from([("owner", "team-a")])));
+        assert!(!filter.matches_tags(&BucketTags::from([("owner", "team-b")])));
+        assert!(!filter.matches_tags(&ignored));
+        assert!(filter.has_tags());
+    }
+
+    #[test]
+    fn test_filter_regions() {
+        let filter = BucketFilter::default()
+            .with_regions(vec![
+                Region::new("eu-west-1"),
+                Region::new("eu-central-1"),
+            ]);
+
+        assert!(filter.matches_region(Some(&Region::new("eu-west-1"))));
+        assert!(!filter.matches_region(Some(&Region::new("us-east-1"))));
+        assert!(!filter.matches_region(None));
+    }
+}
@@ -0,0 +1,136 @@
+// Checks that are made against each bucket
+use crate::s3::{
+    Audit,
+    Severity,
+};
+
+// A single check made against a bucket. Check IDs are stable and must not be
+// changed, as dashboards and baselines use them to track findings between
+// runs.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Check {
+    pub audit:       Audit,
+    pub id:          &'static str,
+    pub description: &'static str,
+    pub severity:    Severity,
+    pub remediation: &'static str,
+}
+
+pub const ACL_PUBLIC_ACCESS: Check = Check {
+    audit:       Audit::Acl,
+    id:          "s3audit/acl-public-access",
+    description: "Bucket ACL doesn't allow public access",
+    severity:    Severity::Critical,
+    remediation: "Remove grants to the AllUsers and AuthenticatedUsers groups \
+                  from the bucket ACL",
+};
+
+pub const BLOCK_PUBLIC_ACLS: Check = Check {
+    audit:       Audit::PublicAccessBlocks,
+    id:          "s3audit/block-public-acls",
+    description: "BlockPublicAcls is enabled",
+    severity:    Severity::Medium,
+    remediation: "Enable the BlockPublicAcls public access block setting",
+};
+
+pub const BLOCK_PUBLIC_POLICY: Check = Check {
+    audit:       Audit::PublicAccessBlocks,
+    id:          "s3audit/block-public-policy",
+    description: "BlockPublicPolicy is enabled",
+    severity:    Severity::Medium,
+    remediation: "Enable the BlockPublicPolicy public access block setting",
+};
+
+pub const IGNORE_PUBLIC_ACLS: Check = Check {
+    audit:       Audit::PublicAccessBlocks,
+    id:          "s3audit/ignore-public-acls",
+    description: "IgnorePublicAcls is enabled",
+    severity:    Severity::Medium,
+    remediation: "Enable the IgnorePublicAcls public access block setting",
+};
+
+pub const LOGGING: Check = Check {
+    audit:       Audit::Logging,
+    id:          "s3audit/logging",
+    description: "Server access logging is enabled",
+    severity:    Severity::Medium,
+    remediation: "Enable server access logging to a dedicated logging bucket",
+};
+
+pub const MFA_DELETE: Check = Check {
+    audit:       Audit::MfaDelete,
+    id:          "s3audit/mfa-delete",
+    description: "MFA Delete is enabled",
+    severity:    Severity::Low,
+    remediation: "Enable MFA Delete on the bucket using the root user's \
+                  credentials",
+};
+
+pub const POLICY_CLOUDFRONT: Check = Check {
+    audit:       Audit::Cloudfront,
+    id:          "s3audit/policy-cloudfront",
+    description: "Bucket is not associated with any CloudFront distributions",
+    severity:    Severity::Low,
+    remediation: "Confirm that the CloudFront distributions granted access by \
+                  the bucket policy are expected to serve this bucket",
+};
+
+pub const POLICY_WILDCARDS: Check = Check {
+    audit:       Audit::Policy,
+    id:          "s3audit/policy-wildcards",
+    description: "Bucket policy doesn't allow a wildcard entity",
+    severity:    Severity::High,
+    remediation: "Replace wildcard actions and principals, NotAction and \
+                  NotResource in the bucket policy's Allow statements with \
+                  specific entries, or limit them with a Condition",
+};
+
+pub const RESTRICT_PUBLIC_BUCKETS: Check = Check {
+    audit:       Audit::PublicAccessBlocks,
+    id:          "s3audit/restrict-public-buckets",
+    description: "RestrictPublicBuckets is enabled",
+    severity:    Severity::Medium,
+    remediation: "Enable the RestrictPublicBuckets public access block setting",
+};
+
+pub const SERVER_SIDE_ENCRYPTION: Check = Check {
+    audit:       Audit::ServerSideEncryption,
+    id:          "s3audit/server-side-encryption",
+    description: "Server side encryption is enabled",
+    severity:    Severity::High,
+    remediation: "Configure default server side encryption on the bucket, \
+                  preferably using a KMS key",
+};
+
+pub const VERSIONING: Check = Check {
+    audit:       Audit::Versioning,
+    id:          "s3audit/versioning",
+    description: "Object Versioning is enabled",
+    severity:    Severity::Medium,
+    remediation: "Enable object versioning on the bucket",
+};
+
+pub const WEBSITE: Check = Check {
+    audit:       Audit::Website,
+    id:          "s3audit/website",
+    description: "Static website hosting is disabled",
+    severity:    Severity::Medium,
+    remediation: "Disable static website hosting, and serve content via \
+                  CloudFront instead",
+};
+
+// Every check we know about
+pub const CHECKS: &[&Check] = &[
+    &ACL_PUBLIC_ACCESS,
+    &BLOCK_PUBLIC_ACLS,
+    &BLOCK_PUBLIC_POLICY,
+    &IGNORE_PUBLIC_ACLS,
+    &LOGGING,
+    &MFA_DELETE,
+    &POLICY_CLOUDFRONT,
+    &POLICY_WILDCARDS,
+    &RESTRICT_PUBLIC_BUCKETS,
+    &SERVER_SIDE_ENCRYPTION,
+    &VERSIONING,
+    &WEBSITE,
+];
@@ -8,28 +8,50 @@ use crate::s3::{
     public_access_block::PublicAccessBlock,
     versioning::BucketVersioning,
     website::BucketWebsite,
+    AccountId,
+    AuditError,
+    BucketFilter,
+    BucketTags,
+    ClientConfig,
+    Organization,
+    Progress,
+    RateLimiter,
     Report,
-    Reports,
+    RetryCounter,
 };
-use anyhow::Result;
-use aws_config::BehaviorVersion;
-use aws_config::meta::region::RegionProviderChain;
+use anyhow::{
+    Context,
+    Result,
+};
+use aws_config::SdkConfig;
 use aws_sdk_s3::client::Client as S3Client;
-use aws_sdk_s3::error::SdkError;
-use aws_sdk_s3::operation::get_bucket_policy::GetBucketPolicyOutput;
 use aws_sdk_s3::types::BucketLocationConstraint;
 use aws_types::region::Region;
+use futures::stream::{
+    self,
+    StreamExt,
+};
 use log::{
     debug,
     info,
+    warn,
 };
-use std::convert::TryInto;
+use std::collections::HashMap;
 use std::fmt;
+use std::sync::{
+    Arc,
+    Mutex,
+};
 
+// A bucket whose region couldn't be found is still reported on, with every
+// audit recorded as an error. Retries are counted from the bucket's discovery
+// through to its audits.
 #[derive(Debug)]
 struct Bucket {
-    name:   String,
-    region: Region,
+    name:    String,
+    region:  Result<Region>,
+    tags:    Result<BucketTags>,
+    retries: RetryCounter,
 }
 
 impl fmt::Display for Bucket {
@@ -38,72 +60,261 @@ impl fmt::Display for Bucket {
     }
 }
 
+// S3 clients for each region, built from the same config
+type RegionalClients = Arc<Mutex<HashMap<Region, S3Client>>>;
+
+// A client for another account has that account's ID, so that it can be
+// recorded on each report.
+//
+// The rate limiter and progress are shared by every client made from this
+// one. Retries are counted per client, and each bucket gets a client with its
+// own count.
+//
+// Bucket tags are only fetched when they're needed, for filtering or grouping
+// by them.
 pub struct Client {
-    client: S3Client,
+    client:   S3Client,
+    config:   SdkConfig,
+    options:  ClientConfig,
+    clients:  RegionalClients,
+    account:  Option<AccountId>,
+    limiter:  Option<Arc<RateLimiter>>,
+    retries:  RetryCounter,
+    progress: Arc<Progress>,
+    tags:     bool,
 }
 
 impl Client {
     // Get a new S3 client
-    pub async fn new(region: Option<Region>) -> Self {
-        info!("Creating new client in region: {:?}", region);
-
-        // Attempt to create a client in the given region, if one isn't given
-        // use the default provider chain, if that fails default to us-east-1.
-        let region_provider = RegionProviderChain::first_try(region)
-            .or_default_provider()
-            .or_else("us-east-1");
-
-        let config = aws_config::defaults(BehaviorVersion::latest())
-            .region(region_provider)
-            .load()
-            .await;
+    pub async fn new(options: ClientConfig) -> Result<Self> {
+        let config = options.load().await?;
+        let client = S3Client::from_conf(options.s3_config(&config).build());
 
-        let client = S3Client::new(&config);
+        let limiter = options.requests_per_second
+            .map(|requests_per_second| {
+                Arc::new(RateLimiter::new(requests_per_second))
+            });
+
+        Ok(Self {
+            client,
+            config,
+            options,
+            limiter,
+            account:  None,
+            clients:  RegionalClients::default(),
+            retries:  RetryCounter::default(),
+            progress: Arc::default(),
+            tags:     false,
+        })
+    }
+
+    // Get a client for another account, by assuming the named role in it
+    pub async fn for_account(
+        &self,
+        account: &AccountId,
+        role_name: &str,
+    ) -> Result<Self> {
+        let options = self.options.for_account(account, role_name);
+        let config  = options.assume_role(&self.config).await?;
+        let client = S3Client::from_conf(options.s3_config(&config).build());
+
+        Ok(Self {
+            client,
+            config,
+            options,
+            account:  Some(account.clone()),
+            clients:  RegionalClients::default(),
+            limiter:  self.limiter.clone(),
+            retries:  RetryCounter::default(),
+            progress: Arc::clone(&self.progress),
+            tags:     self.tags,
+        })
+    }
+
+    // Get a client for the given region
+    // We must interact with buckets from the region they reside in. Clients
+    // are cached, so that the config and credentials are only loaded once.
+    fn regional(&self, region: &Region) -> Self {
+        let client = self.clients
+            .lock()
+            .expect("regional client cache poisoned")
+            .entry(region.clone())
+            .or_insert_with(|| {
+                info!("Creating new client in region: {:?}", region);
+
+                let config = self.options
+                    .s3_config(&self.config)
+                    .region(region.clone())
+                    .build();
+
+                S3Client::from_conf(config)
+            })
+            .clone();
 
         Self {
             client,
+            ..self.counting(&self.retries)
+        }
+    }
+
+    // Get a copy of this client whose requests count against the given
+    // retries
+    fn counting(&self, retries: &RetryCounter) -> Self {
+        Self {
+            client:   self.client.clone(),
+            config:   self.config.clone(),
+            options:  self.options.clone(),
+            clients:  Arc::clone(&self.clients),
+            account:  self.account.clone(),
+            limiter:  self.limiter.clone(),
+            retries:  retries.clone(),
+            progress: Arc::clone(&self.progress),
+            tags:     self.tags,
+        }
+    }
+
+    // Show progress while auditing
+    pub fn with_progress(self, progress: Arc<Progress>) -> Self {
+        Self {
+            progress,
+            ..self
+        }
+    }
+
+    // Lists accounts in the organization using this client's credentials
+    pub fn organization(&self) -> Organization {
+        Organization::new(&self.config)
+    }
+
+    // Fetch each bucket's tags, so that reports can be filtered or grouped
+    // by them
+    pub fn with_tags(self, tags: bool) -> Self {
+        Self {
+            tags,
+            ..self
+        }
+    }
+
+    // The progress shown while auditing, so that other output can be
+    // written around it
+    pub fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    // Waits until the next request can be made, if requests are limited
+    async fn throttle(&self) {
+        if let Some(limiter) = &self.limiter {
+            limiter.wait().await;
         }
     }
 
     // List all buckets on an account
-    // We also get the region for each bucket here as we need to create the S3
-    // client for each bucket in the appropriate location.
-    async fn list_buckets(&self) -> Result<Vec<Bucket>> {
+    async fn list_buckets(&self) -> Result<Vec<String>> {
         info!("Listing buckets");
 
+        self.throttle().await;
+
         let output = self.client
             .list_buckets()
+            .customize()
+            .interceptor(self.retries.clone())
             .send()
             .await?;
 
-        let bucket_names: Vec<&str> = output
+        let bucket_names: Vec<String> = output
             .buckets()
             .iter()
             .filter_map(aws_sdk_s3::types::Bucket::name)
+            .map(ToString::to_string)
             .collect();
 
-        let mut buckets: Vec<Bucket> = Vec::new();
-
-        for bucket in bucket_names {
-            let region = self.get_bucket_region(bucket).await?;
-
-            let bucket = Bucket {
-                region,
-                name:   bucket.to_string(),
-            };
+        Ok(bucket_names)
+    }
 
-            buckets.push(bucket);
-        }
+    // Get the region and tags for each bucket, as we need to create the S3
+    // client for each bucket in the appropriate location. Buckets outside of
+    // the filter's regions, or without its tags, are left out. Up to
+    // `concurrency` buckets are looked up at once.
+    async fn discover_buckets(
+        &self,
+        bucket_names: Vec<String>,
+        filter: &BucketFilter,
+        concurrency: usize,
+    ) -> Vec<Bucket> {
+        let buckets: Vec<Option<Bucket>> = stream::iter(bucket_names)
+            .map(|bucket| {
+                async move {
+                    let retries = RetryCounter::default();
+                    let client = self.counting(&retries);
+                    let region = client.get_bucket_region(&bucket).await;
+
+                    // Checking the region first saves fetching tags
+                    if !filter.matches_region(region.as_ref().ok()) {
+                        info!("Skipping bucket outside of regions: {}", bucket);
+                        return None;
+                    }
+
+                    let tags = match &region {
+                        Ok(region) if self.tags => {
+                            client.regional(region)
+                                .get_bucket_tagging(&bucket)
+                                .await
+                                .context("Could not get bucket tags")
+                        },
+                        _ => Ok(BucketTags::default()),
+                    };
+
+                    // Grouping doesn't need the tags to audit the bucket, so
+                    // without a tag filter a bucket whose tags can't be read
+                    // is audited and grouped as untagged.
+                    let tags = match tags {
+                        Err(error) if !filter.has_tags() => {
+                            warn!("Could not get tags for bucket {}: {:#}", bucket, error);
+
+                            Ok(BucketTags::default())
+                        },
+                        tags => tags,
+                    };
+
+                    // Buckets are only skipped when their tags are known not
+                    // to match. Those whose region or tags couldn't be found
+                    // are kept, and reported as not evaluated.
+                    if let (Ok(_), Ok(tags)) = (&region, &tags) {
+                        if !filter.matches_tags(tags) {
+                            info!("Skipping bucket not matching tags: {}", bucket);
+                            return None;
+                        }
+                    }
+
+                    self.progress.discovered();
+
+                    Some(Bucket {
+                        region,
+                        tags,
+                        retries,
+                        name: bucket,
+                    })
+                }
+            })
+            .buffered(concurrency)
+            .collect()
+            .await;
 
-        Ok(buckets)
+        buckets.into_iter()
+            .flatten()
+            .collect()
     }
 
     async fn get_bucket_acl(&self, bucket: &str) -> Result<BucketAcl> {
         info!("Getting bucket ACL for bucket: {}", bucket);
 
+        self.throttle().await;
+
         let output = self.client
             .get_bucket_acl()
             .bucket(bucket)
+            .customize()
+            .interceptor(self.retries.clone())
             .send()
             .await?;
 
@@ -115,13 +326,17 @@ impl Client {
     async fn get_bucket_encryption(&self, bucket: &str) -> Result<BucketEncryption> {
         info!("Getting bucket encryption for bucket: {}", bucket);
 
+        self.throttle().await;
+
         let output = self.client
             .get_bucket_encryption()
             .bucket(bucket)
+            .customize()
+            .interceptor(self.retries.clone())
             .send()
             .await;
 
-        let config: BucketEncryption = output.into();
+        let config = BucketEncryption::try_from(output)?;
 
         Ok(config)
     }
@@ -129,9 +344,13 @@ impl Client {
     async fn get_bucket_location(&self, bucket: &str) -> Result<String> {
         info!("Getting bucket location for bucket: {}", bucket);
 
+        self.throttle().await;
+
         let output = self.client
             .get_bucket_location()
             .bucket(bucket)
+            .customize()
+            .interceptor(self.retries.clone())
             .send()
             .await?;
 
@@ -156,9 +375,13 @@ impl Client {
     async fn get_bucket_logging(&self, bucket: &str) -> Result<BucketLogging> {
         info!("Getting bucket logging for bucket: {}", bucket);
 
+        self.throttle().await;
+
         let output = self.client
             .get_bucket_logging()
             .bucket(bucket)
+            .customize()
+            .interceptor(self.retries.clone())
             .send()
             .await?;
 
@@ -170,45 +393,19 @@ impl Client {
     async fn get_bucket_policy(&self, bucket: &str) -> Result<Option<BucketPolicy>> {
         info!("Getting bucket policy for bucket: {}", bucket);
 
+        self.throttle().await;
+
         let output = self.client
             .get_bucket_policy()
             .bucket(bucket)
+            .customize()
+            .interceptor(self.retries.clone())
             .send()
             .await;
 
         debug!("get_bucket_policy returned: {:?}", output);
 
-        let output = match output {
-            Ok(item) => Ok(item),
-            Err(error) => {
-                match error {
-                    // Handle specific error cases from the service that aren't
-                    // really error.
-                    // We're just treating all ServiceErrors the same here, but
-                    // we probably want to make this way more specific at some
-                    // point.
-                    SdkError::ServiceError { .. } => {
-                        // Build a basic empty policy
-                        let policy = GetBucketPolicyOutput::builder()
-                            .set_policy(None)
-                            .build();
-
-                        Ok(policy)
-                    },
-
-                    // Anything else is a real error.
-                    _ => Err(error),
-                }
-            }
-        }?;
-
-        // Didn't get 404 but no policy supplied
-        if output.policy.is_none() {
-            return Ok(None);
-        }
-
-        let bucket_policy: BucketPolicy = output.try_into()?;
-        Ok(Some(bucket_policy))
+        BucketPolicy::from_result(output)
     }
 
     async fn get_bucket_region(&self, bucket: &str) -> Result<Region> {
@@ -223,12 +420,35 @@ impl Client {
         Ok(region)
     }
 
+    async fn get_bucket_tagging(&self, bucket: &str) -> Result<BucketTags> {
+        info!("Getting bucket tags for bucket: {}", bucket);
+
+        self.throttle().await;
+
+        // A bucket without tags is an error from the API
+        let output = self.client
+            .get_bucket_tagging()
+            .bucket(bucket)
+            .customize()
+            .interceptor(self.retries.clone())
+            .send()
+            .await;
+
+        let tags = BucketTags::try_from(output)?;
+
+        Ok(tags)
+    }
+
     async fn get_bucket_versioning(&self, bucket: &str) -> Result<BucketVersioning> {
         info!("Getting bucket versioning for bucket: {}", bucket);
 
+        self.throttle().await;
+
         let output = self.client
             .get_bucket_versioning()
             .bucket(bucket)
+            .customize()
+            .interceptor(self.retries.clone())
             .send()
             .await?;
 
@@ -240,14 +460,19 @@ impl Client {
     async fn get_bucket_website(&self, bucket: &str) -> Result<BucketWebsite> {
         info!("Getting bucket website for bucket: {}", bucket);
 
-        // Note that we aren't using the `?` operator here.
+        self.throttle().await;
+
+        // Note that we aren't using the `?` operator here, a missing website
+        // configuration is an error from the API.
         let output = self.client
             .get_bucket_website()
             .bucket(bucket)
+            .customize()
+            .interceptor(self.retries.clone())
             .send()
             .await;
 
-        let config: BucketWebsite = output.into();
+        let config = BucketWebsite::try_from(output)?;
 
         Ok(config)
     }
@@ -256,69 +481,84 @@ impl Client {
     async fn get_public_access_block(&self, bucket: &str) -> Result<PublicAccessBlock> {
         info!("Getting public access block for bucket: {}", bucket);
 
+        self.throttle().await;
+
         let output = self.client
             .get_public_access_block()
             .bucket(bucket)
+            .customize()
+            .interceptor(self.retries.clone())
             .send()
             .await;
 
-        // The error here should be more closely inspected. For now we just
-        // assume that the PublicAccessBlock settings didn't exist, so they
-        // aren't set.
-        let config = match output {
-            Err(_) => PublicAccessBlock::default(),
-            Ok(o)  => o.into(),
-        };
+        let config = PublicAccessBlock::try_from(output)?;
 
         Ok(config)
     }
 
     // Reports on a single bucket
+    // The API calls for each audit are made concurrently. Audits that fail
+    // are recorded as errors on the report, rather than failing the run.
     async fn bucket_report(
         &self,
         bucket: &str,
         audits: &[Audit],
-    ) -> Result<Report> {
+    ) -> Report {
         info!("Generating report for bucket: {}", bucket);
 
-        let acl = if audits.contains(&Audit::Acl) {
-            let resp = self.get_bucket_acl(bucket).await?;
-            Some(resp)
-        }
-        else {
-            None
+        let acl = async {
+            if audits.contains(&Audit::Acl) {
+                self.get_bucket_acl(bucket)
+                    .await
+                    .map(Some)
+            }
+            else {
+                Ok(None)
+            }
         };
 
-        let encryption = if audits.contains(&Audit::ServerSideEncryption) {
-            let resp = self.get_bucket_encryption(bucket).await?;
-            Some(resp)
-        }
-        else {
-            None
+        let encryption = async {
+            if audits.contains(&Audit::ServerSideEncryption) {
+                self.get_bucket_encryption(bucket)
+                    .await
+                    .map(Some)
+            }
+            else {
+                Ok(None)
+            }
         };
 
-        let logging = if audits.contains(&Audit::Logging) {
-            let resp = self.get_bucket_logging(bucket).await?;
-            Some(resp)
-        }
-        else {
-            None
+        let logging = async {
+            if audits.contains(&Audit::Logging) {
+                self.get_bucket_logging(bucket)
+                    .await
+                    .map(Some)
+            }
+            else {
+                Ok(None)
+            }
         };
 
-        let policy = if audits.contains(&Audit::Policy) {
-            let resp = self.get_bucket_policy(bucket).await?;
-            Some(resp)
-        }
-        else {
-            None
+        let policy = async {
+            if audits.contains(&Audit::Policy) {
+                self.get_bucket_policy(bucket)
+                    .await
+                    .map(Some)
+            }
+            else {
+                Ok(None)
+            }
         };
 
-        let public_access_block = if audits.contains(&Audit::PublicAccessBlocks) {
-            let resp = self.get_public_access_block(bucket).await?;
-            Some(resp)
-        }
-        else {
-            None
+        let public_access_block = async {
+            if audits.contains(&Audit::PublicAccessBlocks) {
+                self.get_public_access_block(bucket)
+                    .await
+                    .map(Some)
+            }
+            else {
+                Ok(None)
+            }
         };
 
         // Both of these come from the Versioning API, so enabled either of
@@ -332,23 +572,29 @@ impl Client {
             .iter()
             .any(|x| audits.contains(x));
 
-        let versioning = if audit_versioning {
-            let resp = self.get_bucket_versioning(bucket).await?;
-            Some(resp)
-        }
-        else {
-            None
+        let versioning = async {
+            if audit_versioning {
+                self.get_bucket_versioning(bucket)
+                    .await
+                    .map(Some)
+            }
+            else {
+                Ok(None)
+            }
         };
 
-        let website = if audits.contains(&Audit::Website) {
-            let resp = self.get_bucket_website(bucket).await?;
-            Some(resp)
-        }
-        else {
-            None
+        let website = async {
+            if audits.contains(&Audit::Website) {
+                self.get_bucket_website(bucket)
+                    .await
+                    .map(Some)
+            }
+            else {
+                Ok(None)
+            }
         };
 
-        let report = Report {
+        let (
             acl,
             encryption,
             logging,
@@ -356,47 +602,186 @@ impl Client {
             public_access_block,
             versioning,
             website,
-            name: bucket.into(),
-        };
+        ) = tokio::join!(
+            acl,
+            encryption,
+            logging,
+            policy,
+            public_access_block,
+            versioning,
+            website,
+        );
+
+        let mut errors = Vec::new();
+
+        let acl = evaluated(bucket, acl, &[Audit::Acl], &mut errors);
 
-        Ok(report)
+        let encryption = evaluated(
+            bucket,
+            encryption,
+            &[Audit::ServerSideEncryption],
+            &mut errors,
+        );
+
+        let logging = evaluated(bucket, logging, &[Audit::Logging], &mut errors);
+
+        let policy = evaluated(
+            bucket,
+            policy,
+            &[Audit::Policy, Audit::Cloudfront],
+            &mut errors,
+        );
+
+        let public_access_block = evaluated(
+            bucket,
+            public_access_block,
+            &[Audit::PublicAccessBlocks],
+            &mut errors,
+        );
+
+        let versioning = evaluated(
+            bucket,
+            versioning,
+            &[Audit::Versioning, Audit::MfaDelete],
+            &mut errors,
+        );
+
+        let website = evaluated(bucket, website, &[Audit::Website], &mut errors);
+
+        // This client was made for the bucket, so these are its retries,
+        // including those from discovering it
+        debug!("Bucket {} needed {} retries", bucket, self.retries.retries());
+
+        Report {
+            acl,
+            encryption,
+            logging,
+            policy,
+            public_access_block,
+            versioning,
+            website,
+            errors,
+            ..Report::new(bucket)
+        }
     }
 
-    // Reports on all discovered buckets
-    pub async fn report(
+    // Reports on the given buckets, or all discovered buckets if none are
+    // given, that match the filter. Up to `concurrency` buckets are audited
+    // at once.
+    //
+    // Each report is passed to `on_report` as soon as it's ready, in the order
+    // buckets finish, rather than being kept. An error from `on_report` stops
+    // the run.
+    pub async fn report<F>(
         &self,
-        bucket: Option<String>,
+        buckets: Vec<String>,
+        filter: &BucketFilter,
         audits: Vec<Audit>,
-    ) -> Result<Reports> {
-        let buckets = match bucket {
-            None         => self.list_buckets().await?,
-            Some(bucket) => {
-                let region = self.get_bucket_region(&bucket).await?;
-                let bucket = Bucket {
-                    region,
-                    name: bucket,
-                };
-
-                vec![bucket]
+        concurrency: usize,
+        on_report: &mut F,
+    ) -> Result<()>
+    where F: FnMut(Report) -> Result<()>,
+    {
+        // Buckets only belong to one account, so when auditing several
+        // accounts the given buckets are only audited in the account that
+        // owns them.
+        let bucket_names = match (buckets.is_empty(), &self.account) {
+            (true, _)        => self.list_buckets().await?,
+            (false, None)    => buckets,
+            (false, Some(_)) => {
+                self.list_buckets()
+                    .await?
+                    .into_iter()
+                    .filter(|name| buckets.contains(name))
+                    .collect()
             },
         };
 
-        info!("Generating reports for buckets: {:?}", buckets);
+        // Filtering by name before looking up regions and tags saves API
+        // calls
+        let bucket_names: Vec<String> = bucket_names.into_iter()
+            .filter(|name| filter.matches(name))
+            .collect();
 
-        let mut reports = Vec::new();
+        let buckets = self.discover_buckets(bucket_names, filter, concurrency)
+            .await;
+
+        info!("Generating reports for buckets: {:?}", buckets);
 
-        // We get a new client for each bucket, as we must interact with
-        // buckets from the region they reside in.
-        for bucket in &buckets {
-            let region = Some(bucket.region.clone());
-            let client = Self::new(region).await;
-            let report = client.bucket_report(&bucket.name, &audits).await?;
+        let audits = &audits;
+
+        self.progress.auditing();
+
+        let mut stream = stream::iter(&buckets)
+            .map(|bucket| {
+                async move {
+                    let report = match (&bucket.region, &bucket.tags) {
+                        (Ok(region), Ok(_)) => {
+                            self.counting(&bucket.retries)
+                                .regional(region)
+                                .bucket_report(&bucket.name, audits)
+                                .await
+                        },
+                        (Err(error), _) | (_, Err(error)) => {
+                            unevaluated_report(&bucket.name, audits, error)
+                        },
+                    };
+
+                    Report {
+                        account: self.account.clone(),
+                        region:  bucket.region.as_ref().ok().map(ToString::to_string),
+                        tags:    bucket.tags.as_ref().cloned().unwrap_or_default(),
+                        ..report
+                    }
+                }
+            })
+            .buffer_unordered(concurrency);
 
-            reports.push(report);
+        while let Some(report) = stream.next().await {
+            self.progress.audited(&report);
+            self.progress.suspend(|| on_report(report))?;
         }
 
-        let reports = Reports::new(reports);
+        Ok(())
+    }
+}
+
+// Returns the result of an audit, recording an error against each of the
+// given audits if it couldn't be evaluated.
+fn evaluated<T>(
+    bucket: &str,
+    result: Result<Option<T>>,
+    audits: &[Audit],
+    errors: &mut Vec<AuditError>,
+) -> Option<T> {
+    match result {
+        Ok(value)  => value,
+        Err(error) => {
+            warn!("Could not evaluate {:?} for bucket {}: {:#}", audits, bucket, error);
+
+            for audit in audits {
+                errors.push(AuditError::new(audit.clone(), &error));
+            }
+
+            None
+        },
+    }
+}
 
-        Ok(reports)
+// A report on a bucket that couldn't be audited at all
+fn unevaluated_report(
+    bucket: &str,
+    audits: &[Audit],
+    error: &anyhow::Error,
+) -> Report {
+    warn!("Could not audit bucket {}: {:#}", bucket, error);
+
+    let errors = audits.iter()
+        .map(|audit| AuditError::new(audit.clone(), error))
+        .collect();
+
+    Report {
+        errors,
+        ..Report::new(bucket)
     }
 }
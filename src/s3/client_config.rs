@@ -0,0 +1,402 @@
+// How the S3 client connects to AWS, and which credentials it uses
+use anyhow::{
+    Context,
+    Result,
+};
+use aws_config::{
+    BehaviorVersion,
+    SdkConfig,
+};
+use aws_config::environment::EnvironmentVariableRegionProvider;
+use aws_config::meta::region::RegionProviderChain;
+use aws_config::profile::ProfileFileRegionProvider;
+use aws_config::retry::{
+    RetryConfig,
+    RetryMode,
+};
+use aws_config::sts::AssumeRoleProvider;
+use aws_sdk_s3::config::Builder as S3ConfigBuilder;
+use aws_sdk_s3::config::{
+    Credentials,
+    SharedCredentialsProvider,
+};
+use aws_sdk_sts::client::Client as StsClient;
+use aws_types::region::Region;
+use crate::s3::AccountId;
+use log::info;
+use std::time::{
+    Duration,
+    SystemTime,
+};
+
+// A one time code from the MFA device protecting a role
+#[derive(Clone, Debug)]
+pub struct Mfa {
+    pub serial:     String,
+    pub token_code: String,
+}
+
+// Everything is optional, falling back to the default AWS config.
+//
+// An endpoint URL allows S3 compatible stores, and local emulators, to be
+// audited. These often need path style addressing, as they can't serve a
+// hostname per bucket.
+//
+// Retries use the SDK's retry config, which backs off when throttled. The
+// requests per second cap is applied by the client to each S3 API call, across
+// all regions and accounts, so retries of a call aren't counted.
+#[derive(Clone, Debug, Default)]
+pub struct ClientConfig {
+    pub profile:             Option<String>,
+    pub region:              Option<Region>,
+    pub role_arn:            Option<String>,
+    pub external_id:         Option<String>,
+    pub mfa:                 Option<Mfa>,
+    pub session_name:        Option<String>,
+    pub session_duration:    Option<Duration>,
+    pub endpoint_url:        Option<String>,
+    pub force_path_style:    bool,
+    pub retry_mode:          Option<RetryMode>,
+    pub max_attempts:        Option<u32>,
+    pub requests_per_second: Option<u32>,
+}
+
+impl ClientConfig {
+    // Loads the shared SDK config, assuming the role if one was given
+    pub async fn load(&self) -> Result<SdkConfig> {
+        info!("Loading AWS config in region: {:?}", self.region);
+
+        // Attempt to use the given region, if one isn't given use the default
+        // provider chain, if that fails default to us-east-1. The default
+        // chain doesn't know about the profile, so its region is checked
+        // after the environment, as the AWS CLI does.
+        let mut region_provider = RegionProviderChain::first_try(self.region.clone());
+
+        if let Some(profile) = &self.profile {
+            let profile_region = ProfileFileRegionProvider::builder()
+                .profile_name(profile)
+                .build();
+
+            region_provider = region_provider
+                .or_else(EnvironmentVariableRegionProvider::new())
+                .or_else(profile_region);
+        }
+
+        let region_provider = region_provider
+            .or_default_provider()
+            .or_else("us-east-1");
+
+        let mut loader = aws_config::defaults(BehaviorVersion::latest())
+            .region(region_provider);
+
+        if let Some(profile) = &self.profile {
+            loader = loader.profile_name(profile);
+        }
+
+        if let Some(retry_config) = self.retry_config() {
+            loader = loader.retry_config(retry_config);
+        }
+
+        let config = loader.load().await;
+
+        self.assume_role(&config).await
+    }
+
+    // Options for the named role in another account, which is assumed using
+    // the credentials of the role given here, if there was one. The external
+    // ID and session settings apply to these roles too, but MFA is only used
+    // for the role given here.
+    pub fn for_account(&self, account: &AccountId, role_name: &str) -> Self {
+        Self {
+            role_arn: Some(account.role_arn(role_name)),
+            mfa:      None,
+            ..self.clone()
+        }
+    }
+
+    // Returns the config with credentials for the role, if one was given,
+    // taken from the current credentials in the config.
+    pub async fn assume_role(&self, config: &SdkConfig) -> Result<SdkConfig> {
+        let Some(role_arn) = &self.role_arn else {
+            return Ok(config.clone());
+        };
+
+        info!("Assuming role: {}", role_arn);
+
+        // MFA codes can only be used once, so the role is assumed straight
+        // away. Otherwise credentials are fetched, and refreshed, as needed.
+        let provider = match &self.mfa {
+            Some(mfa) => {
+                let credentials = self.assume_role_with_mfa(config, role_arn, mfa)
+                    .await?;
+
+                SharedCredentialsProvider::new(credentials)
+            },
+            None => {
+                let mut builder = AssumeRoleProvider::builder(role_arn)
+                    .session_name(self.session_name())
+                    .configure(config);
+
+                if let Some(external_id) = &self.external_id {
+                    builder = builder.external_id(external_id);
+                }
+
+                if let Some(duration) = self.session_duration {
+                    builder = builder.session_length(duration);
+                }
+
+                SharedCredentialsProvider::new(builder.build().await)
+            },
+        };
+
+        let config = config.to_builder()
+            .credentials_provider(provider)
+            .build();
+
+        Ok(config)
+    }
+
+    async fn assume_role_with_mfa(
+        &self,
+        config: &SdkConfig,
+        role_arn: &str,
+        mfa: &Mfa,
+    ) -> Result<Credentials> {
+        let duration = self.session_duration
+            .map(|duration| i32::try_from(duration.as_secs()))
+            .transpose()?;
+
+        let output = StsClient::new(config)
+            .assume_role()
+            .role_arn(role_arn)
+            .role_session_name(self.session_name())
+            .set_external_id(self.external_id.clone())
+            .set_duration_seconds(duration)
+            .serial_number(&mfa.serial)
+            .token_code(&mfa.token_code)
+            .send()
+            .await
+            .with_context(|| format!("Could not assume role {}", role_arn))?;
+
+        let credentials = output.credentials()
+            .with_context(|| format!("No credentials returned for {}", role_arn))?;
+
+        let expiry = SystemTime::try_from(*credentials.expiration()).ok();
+
+        let credentials = Credentials::new(
+            credentials.access_key_id(),
+            credentials.secret_access_key(),
+            Some(credentials.session_token().into()),
+            expiry,
+            "AssumeRoleWithMfa",
+        );
+
+        Ok(credentials)
+    }
+
+    // Without a mode or max attempts, the retry config comes from the
+    // environment or profile, as with the AWS CLI.
+    fn retry_config(&self) -> Option<RetryConfig> {
+        if self.retry_mode.is_none() && self.max_attempts.is_none() {
+            return None;
+        }
+
+        let retry_config = match self.retry_mode {
+            Some(RetryMode::Adaptive) => RetryConfig::adaptive(),
+            _                         => RetryConfig::standard(),
+        };
+
+        let retry_config = match self.max_attempts {
+            Some(max_attempts) => retry_config.with_max_attempts(max_attempts),
+            None               => retry_config,
+        };
+
+        Some(retry_config)
+    }
+
+    // Shows up in CloudTrail, so it should be clear what's making requests
+    fn session_name(&self) -> String {
+        self.session_name
+            .clone()
+            .unwrap_or_else(|| env!("CARGO_PKG_NAME").into())
+    }
+
+    // Builds the S3 config from the shared SDK config
+    pub fn s3_config(&self, config: &SdkConfig) -> S3ConfigBuilder {
+        let mut builder = S3ConfigBuilder::from(config);
+
+        if let Some(endpoint_url) = &self.endpoint_url {
+            builder = builder.endpoint_url(endpoint_url);
+        }
+
+        if self.force_path_style {
+            builder = builder.force_path_style(true);
+        }
+
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_s3::client::Client as S3Client;
+    use aws_sdk_s3::config::interceptors::BeforeTransmitInterceptorContextRef;
+    use aws_sdk_s3::config::{
+        ConfigBag,
+        Intercept,
+        RuntimeComponents,
+    };
+    use aws_sdk_s3::error::BoxError;
+    use pretty_assertions::assert_eq;
+    use std::sync::{
+        Arc,
+        Mutex,
+    };
+
+    // Records the URI of a request, and stops it from being sent
+    #[derive(Clone, Debug, Default)]
+    struct CaptureUri(Arc<Mutex<Option<String>>>);
+
+    impl Intercept for CaptureUri {
+        fn name(&self) -> &'static str {
+            "CaptureUri"
+        }
+
+        fn read_before_transmit(
+            &self,
+            context: &BeforeTransmitInterceptorContextRef<'_>,
+            _runtime_components: &RuntimeComponents,
+            _cfg: &mut ConfigBag,
+        ) -> Result<(), BoxError> {
+            *self.0.lock().unwrap() = Some(context.request().uri().to_string());
+
+            Err("request captured".into())
+        }
+    }
+
+    // The URI that getting a bucket's ACL would be sent to
+    async fn request_uri(options: &ClientConfig) -> String {
+        let credentials = Credentials::new("AKID", "SECRET", None, None, "test");
+
+        let config = SdkConfig::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("eu-west-1"))
+            .credentials_provider(SharedCredentialsProvider::new(credentials))
+            .build();
+
+        let capture = CaptureUri::default();
+
+        let s3_config = options.s3_config(&config)
+            .interceptor(capture.clone())
+            .build();
+
+        let _ = S3Client::from_conf(s3_config)
+            .get_bucket_acl()
+            .bucket("example-bucket")
+            .send()
+            .await;
+
+        let uri = capture.0.lock().unwrap().clone();
+
+        uri.unwrap()
+    }
+
+    #[test]
+    fn test_retry_config_unset() {
+        let options = ClientConfig::default();
+
+        assert!(options.retry_config().is_none());
+    }
+
+    #[test]
+    fn test_retry_config() {
+        let tests = vec![
+            (Some(RetryMode::Adaptive), Some(10), RetryMode::Adaptive, 10),
+            (Some(RetryMode::Adaptive), None,     RetryMode::Adaptive, 3),
+            (Some(RetryMode::Standard), None,     RetryMode::Standard, 3),
+            (None,                      Some(5),  RetryMode::Standard, 5),
+        ];
+
+        for (retry_mode, max_attempts, expected_mode, expected_attempts) in tests {
+            let options = ClientConfig {
+                retry_mode,
+                max_attempts,
+                ..ClientConfig::default()
+            };
+
+            let retry_config = options.retry_config().unwrap();
+
+            assert_eq!(retry_config.mode(), expected_mode);
+            assert_eq!(retry_config.max_attempts(), expected_attempts);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_s3_config_default() {
+        let options = ClientConfig::default();
+
+        assert_eq!(
+            request_uri(&options).await,
+            "https://example-bucket.s3.eu-west-1.amazonaws.com/?acl",
+        );
+    }
+
+    #[tokio::test]
+    async fn test_s3_config_endpoint_url() {
+        let options = ClientConfig {
+            endpoint_url: Some("https://s3.example.com".into()),
+            ..ClientConfig::default()
+        };
+
+        assert_eq!(
+            request_uri(&options).await,
+            "https://example-bucket.s3.example.com/?acl",
+        );
+
+        let options = ClientConfig {
+            force_path_style: true,
+            ..options
+        };
+
+        assert_eq!(
+            request_uri(&options).await,
+            "https://s3.example.com/example-bucket/?acl",
+        );
+    }
+
+    #[test]
+    fn test_for_account() {
+        let options = ClientConfig {
+            role_arn:     Some("arn:aws:iam::999999999999:role/auditor".into()),
+            external_id:  Some("example-id".into()),
+            session_name: Some("example-session".into()),
+            mfa:          Some(Mfa {
+                serial:     "arn:aws:iam::999999999999:mfa/jane".into(),
+                token_code: "123456".into(),
+            }),
+            ..ClientConfig::default()
+        };
+
+        let account: AccountId = "111111111111".parse().unwrap();
+        let account_options = options.for_account(&account, "s3audit");
+
+        // The named role replaces the given role, which has already been
+        // assumed, and the MFA code has already been used.
+        assert_eq!(
+            account_options.role_arn.as_deref(),
+            Some("arn:aws:iam::111111111111:role/s3audit"),
+        );
+        assert!(account_options.mfa.is_none());
+
+        assert_eq!(account_options.external_id, options.external_id);
+        assert_eq!(account_options.session_name(), "example-session");
+    }
+
+    #[test]
+    fn test_session_name() {
+        let options = ClientConfig::default();
+
+        assert_eq!(options.session_name(), "s3audit");
+    }
+}
@@ -0,0 +1,457 @@
+// Differences between two saved reports
+use anyhow::Result;
+use colored::Colorize;
+use crate::common::Emoji;
+use crate::s3::{
+    SavedBucket,
+    SavedReport,
+    Status,
+};
+use std::io::Write;
+
+// A check whose status changed between runs. A status of None means that the
+// check wasn't in that report.
+#[derive(Debug, PartialEq)]
+pub struct CheckChange {
+    pub check:   String,
+    pub old:     Option<Status>,
+    pub new:     Option<Status>,
+    pub message: String,
+}
+
+impl CheckChange {
+    // A check that now needs fixing, where it didn't before
+    pub fn is_regression(&self) -> bool {
+        is_failure(self.new) && !is_failure(self.old)
+    }
+
+    // A check that no longer needs fixing. A check that couldn't be
+    // evaluated may still need fixing, so isn't a fix.
+    pub fn is_fix(&self) -> bool {
+        is_failure(self.old) && !is_failure(self.new) && !is_error(self.new)
+    }
+
+    // A check that couldn't be evaluated this time, so whether it needs
+    // fixing isn't known
+    pub fn is_unevaluated(&self) -> bool {
+        is_error(self.new) && !is_error(self.old)
+    }
+}
+
+fn is_failure(status: Option<Status>) -> bool {
+    status.is_some_and(Status::is_failure)
+}
+
+fn is_error(status: Option<Status>) -> bool {
+    status == Some(Status::Error)
+}
+
+fn status_name(status: Option<Status>) -> String {
+    status.map_or_else(|| "none".into(), |status| status.to_string())
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BucketState {
+    Added,
+    Changed,
+    Removed,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BucketDiff {
+    pub name:    String,
+    pub state:   BucketState,
+    pub changes: Vec<CheckChange>,
+}
+
+#[derive(Debug)]
+pub struct Diff {
+    buckets: Vec<BucketDiff>,
+}
+
+// Compares the findings for a bucket between two runs. Only changes that
+// matter are kept, so new passing checks and passes becoming info are ignored.
+// Checks that can no longer be evaluated are kept, as their state is unknown.
+fn bucket_changes(old: Option<&SavedBucket>, new: &SavedBucket) -> Vec<CheckChange> {
+    let mut changes = Vec::new();
+
+    for finding in &new.findings {
+        let old_status = old
+            .and_then(|bucket| bucket.finding(new, finding))
+            .map(|finding| finding.status);
+
+        let new_status = Some(finding.status);
+
+        let changed = is_failure(old_status) != is_failure(new_status)
+            || (is_failure(old_status) && old_status != new_status)
+            || (is_error(new_status) && !is_error(old_status));
+
+        if changed {
+            changes.push(CheckChange {
+                check:   finding.check.to_string(),
+                old:     old_status,
+                new:     new_status,
+                message: finding.message.to_string(),
+            });
+        }
+    }
+
+    // Failing checks that have disappeared, perhaps because the audit is no
+    // longer being run.
+    if let Some(old) = old {
+        for finding in &old.findings {
+            let missing = new.finding(old, finding)
+                .is_none();
+
+            if missing && finding.status.is_failure() {
+                changes.push(CheckChange {
+                    check:   finding.check.to_string(),
+                    old:     Some(finding.status),
+                    new:     None,
+                    message: finding.message.to_string(),
+                });
+            }
+        }
+    }
+
+    changes
+}
+
+impl Diff {
+    pub fn new(old: &SavedReport, new: &SavedReport) -> Self {
+        let mut buckets = Vec::new();
+
+        for bucket in &new.buckets {
+            let old_bucket = old.bucket(&bucket.name);
+
+            let state = if old_bucket.is_some() {
+                BucketState::Changed
+            }
+            else {
+                BucketState::Added
+            };
+
+            let changes = bucket_changes(old_bucket, bucket);
+
+            if state == BucketState::Added || !changes.is_empty() {
+                buckets.push(BucketDiff {
+                    state,
+                    changes,
+                    name: bucket.name.to_string(),
+                });
+            }
+        }
+
+        for bucket in &old.buckets {
+            if new.bucket(&bucket.name).is_none() {
+                buckets.push(BucketDiff {
+                    name:    bucket.name.to_string(),
+                    state:   BucketState::Removed,
+                    changes: Vec::new(),
+                });
+            }
+        }
+
+        Self {
+            buckets,
+        }
+    }
+
+    pub fn regressions(&self) -> usize {
+        self.buckets.iter()
+            .flat_map(|bucket| &bucket.changes)
+            .filter(|change| change.is_regression())
+            .count()
+    }
+
+    pub fn fixes(&self) -> usize {
+        self.buckets.iter()
+            .flat_map(|bucket| &bucket.changes)
+            .filter(|change| change.is_fix())
+            .count()
+    }
+
+    pub fn unevaluated(&self) -> usize {
+        self.buckets.iter()
+            .flat_map(|bucket| &bucket.changes)
+            .filter(|change| change.is_unevaluated())
+            .count()
+    }
+
+    // Simple text output
+    pub fn text<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        for bucket in &self.buckets {
+            let state = match bucket.state {
+                BucketState::Added   => " (new bucket)",
+                BucketState::Changed => "",
+                BucketState::Removed => " (removed)",
+            };
+
+            writeln!(
+                writer,
+                "  {} {}{}",
+                Emoji::Arrow,
+                bucket.name.bold().blue(),
+                state,
+            )?;
+
+            for change in &bucket.changes {
+                let emoji = if change.is_fix() {
+                    Emoji::Tick
+                }
+                else {
+                    change.new.map_or(Emoji::Info, Emoji::from)
+                };
+
+                writeln!(
+                    writer,
+                    "    {} {}: {} -> {}: {}",
+                    emoji,
+                    change.check,
+                    status_name(change.old),
+                    status_name(change.new),
+                    change.message,
+                )?;
+            }
+        }
+
+        writeln!(
+            writer,
+            "  {} {} regressions, {} fixed, {} could not be evaluated",
+            Emoji::Arrow,
+            self.regressions(),
+            self.fixes(),
+            self.unevaluated(),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::SavedFinding;
+    use pretty_assertions::assert_eq;
+
+    fn finding(check: &str, status: Status) -> SavedFinding {
+        SavedFinding {
+            status,
+            check:   check.into(),
+            message: format!("{} is {}", check, status),
+        }
+    }
+
+    fn bucket(name: &str, findings: Vec<SavedFinding>) -> SavedBucket {
+        SavedBucket {
+            findings,
+            name: name.into(),
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = SavedReport {
+            buckets: vec![
+                bucket("unchanged", vec![
+                    finding("s3audit/logging", Status::Fail),
+                ]),
+                bucket("changed", vec![
+                    finding("s3audit/acl-public-access", Status::Pass),
+                    finding("s3audit/logging", Status::Fail),
+                    finding("s3audit/versioning", Status::Pass),
+                ]),
+                bucket("removed", vec![]),
+            ],
+        };
+
+        let new = SavedReport {
+            buckets: vec![
+                bucket("unchanged", vec![
+                    finding("s3audit/logging", Status::Fail),
+                ]),
+                bucket("changed", vec![
+                    finding("s3audit/acl-public-access", Status::Fail),
+                    finding("s3audit/logging", Status::Pass),
+                    finding("s3audit/versioning", Status::Pass),
+                ]),
+                bucket("added", vec![
+                    finding("s3audit/acl-public-access", Status::Pass),
+                    finding("s3audit/website", Status::Warn),
+                ]),
+            ],
+        };
+
+        let diff = Diff::new(&old, &new);
+
+        let expected = vec![
+            BucketDiff {
+                name:    "changed".into(),
+                state:   BucketState::Changed,
+                changes: vec![
+                    CheckChange {
+                        check:   "s3audit/acl-public-access".into(),
+                        old:     Some(Status::Pass),
+                        new:     Some(Status::Fail),
+                        message: "s3audit/acl-public-access is fail".into(),
+                    },
+                    CheckChange {
+                        check:   "s3audit/logging".into(),
+                        old:     Some(Status::Fail),
+                        new:     Some(Status::Pass),
+                        message: "s3audit/logging is pass".into(),
+                    },
+                ],
+            },
+            BucketDiff {
+                name:    "added".into(),
+                state:   BucketState::Added,
+                changes: vec![
+                    CheckChange {
+                        check:   "s3audit/website".into(),
+                        old:     None,
+                        new:     Some(Status::Warn),
+                        message: "s3audit/website is warn".into(),
+                    },
+                ],
+            },
+            BucketDiff {
+                name:    "removed".into(),
+                state:   BucketState::Removed,
+                changes: vec![],
+            },
+        ];
+
+        assert_eq!(diff.buckets, expected);
+        assert_eq!(diff.regressions(), 2);
+        assert_eq!(diff.fixes(), 1);
+    }
+
+    #[test]
+    fn test_missing_failure() {
+        let old = bucket("bucket", vec![
+            finding("s3audit/logging", Status::Fail),
+        ]);
+
+        let new = bucket("bucket", vec![]);
+
+        let changes = bucket_changes(Some(&old), &new);
+
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].is_fix());
+    }
+
+    #[test]
+    fn test_failure_to_error() {
+        let old = bucket("bucket", vec![
+            finding("s3audit/logging", Status::Fail),
+            finding("s3audit/versioning", Status::Pass),
+        ]);
+
+        let new = bucket("bucket", vec![
+            finding("s3audit/logging", Status::Error),
+            finding("s3audit/versioning", Status::Error),
+        ]);
+
+        let changes = bucket_changes(Some(&old), &new);
+
+        // Neither check is known to be fixed or broken
+        assert_eq!(changes.len(), 2);
+
+        for change in &changes {
+            assert!(change.is_unevaluated());
+            assert!(!change.is_fix());
+            assert!(!change.is_regression());
+        }
+    }
+
+    #[test]
+    fn test_several_findings_for_a_check() {
+        let findings = || vec![
+            SavedFinding {
+                check:   "s3audit/policy-wildcards".into(),
+                status:  Status::Fail,
+                message: "Statement \"A\" allows wildcard Action \"s3:*\"".into(),
+            },
+            SavedFinding {
+                check:   "s3audit/policy-wildcards".into(),
+                status:  Status::Warn,
+                message: "Statement \"B\" allows wildcard Action \"s3:*\"".into(),
+            },
+        ];
+
+        let old = bucket("bucket", findings());
+        let new = bucket("bucket", findings());
+
+        let changes = bucket_changes(Some(&old), &new);
+
+        assert_eq!(changes, vec![]);
+    }
+
+    #[test]
+    fn test_added_finding_for_a_check() {
+        let statement = |sid: &str| {
+            SavedFinding {
+                check:   "s3audit/policy-wildcards".into(),
+                status:  Status::Fail,
+                message: format!("Statement \"{}\" allows wildcard Action \"s3:*\"", sid),
+            }
+        };
+
+        let old = bucket("bucket", vec![statement("A")]);
+        let new = bucket("bucket", vec![statement("A"), statement("B")]);
+
+        // The new statement isn't matched to the one that was already failing
+        let changes = bucket_changes(Some(&old), &new);
+
+        assert_eq!(changes, vec![
+            CheckChange {
+                check:   "s3audit/policy-wildcards".into(),
+                old:     None,
+                new:     Some(Status::Fail),
+                message: statement("B").message,
+            },
+        ]);
+        assert!(changes[0].is_regression());
+
+        // A removed statement is fixed, rather than matched to the one left
+        let changes = bucket_changes(Some(&new), &old);
+
+        assert_eq!(changes, vec![
+            CheckChange {
+                check:   "s3audit/policy-wildcards".into(),
+                old:     Some(Status::Fail),
+                new:     None,
+                message: statement("B").message,
+            },
+        ]);
+        assert!(changes[0].is_fix());
+    }
+
+    #[test]
+    fn test_changed_message_for_a_check() {
+        let old = bucket("bucket", vec![
+            SavedFinding {
+                check:   "s3audit/server-side-encryption".into(),
+                status:  Status::Fail,
+                message: "Server side encryption is not enabled".into(),
+            },
+        ]);
+
+        let new = bucket("bucket", vec![
+            SavedFinding {
+                check:   "s3audit/server-side-encryption".into(),
+                status:  Status::Pass,
+                message: "Server side encryption is enabled with SSE-KMS".into(),
+            },
+        ]);
+
+        // A single finding on each side is the same finding
+        let changes = bucket_changes(Some(&old), &new);
+
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].is_fix());
+    }
+}
@@ -1,22 +1,34 @@
 // Bucket encryption config
-use crate::common::Emoji;
-use aws_sdk_s3::error::SdkError;
+use crate::s3::{
+    Finding,
+    Findings,
+    Severity,
+    SERVER_SIDE_ENCRYPTION,
+};
+use aws_sdk_s3::error::{
+    ProvideErrorMetadata,
+    SdkError,
+};
 use aws_sdk_s3::operation::get_bucket_encryption::{
     GetBucketEncryptionError,
     GetBucketEncryptionOutput,
 };
 use aws_sdk_s3::types::ServerSideEncryption;
-use std::fmt;
+use serde::ser::{
+    Serialize,
+    SerializeStruct,
+    Serializer,
+};
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum BucketEncryption {
     Default,
-    Kms,
+    Kms(Option<String>),
     None,
     Unknown(String),
 }
 
-// Type alias to avoid long lines in From impl.
+// Type alias to avoid long lines in TryFrom impl.
 type EncryptionResult = Result<
     GetBucketEncryptionOutput,
     SdkError<GetBucketEncryptionError>,
@@ -25,7 +37,7 @@ type EncryptionResult = Result<
 // Could probably replace a log of this with some .and_then shenanigans.
 impl From<GetBucketEncryptionOutput> for BucketEncryption {
     fn from(output: GetBucketEncryptionOutput) -> Self {
-        let sse_algorithm = output
+        let default_rule = output
             .server_side_encryption_configuration
             .map(|config| config.rules)
             .and_then(|rules| {
@@ -37,66 +49,111 @@ impl From<GetBucketEncryptionOutput> for BucketEncryption {
                     rules.first().cloned()
                 }
             })
-            .and_then(|rule| rule.apply_server_side_encryption_by_default)
-            .map(|rule| rule.sse_algorithm);
-
-        match sse_algorithm {
-            None                               => Self::None,
-            Some(ServerSideEncryption::Aes256) => Self::Default,
-            Some(ServerSideEncryption::AwsKms) => Self::Kms,
-            Some(unknown)                      => {
+            .and_then(|rule| rule.apply_server_side_encryption_by_default);
+
+        let Some(rule) = default_rule else {
+            return Self::None;
+        };
+
+        match rule.sse_algorithm {
+            ServerSideEncryption::Aes256 => Self::Default,
+            ServerSideEncryption::AwsKms => Self::Kms(rule.kms_master_key_id),
+            unknown                      => {
                 Self::Unknown(unknown.as_str().into())
             },
         }
     }
 }
 
-impl From<EncryptionResult> for BucketEncryption {
-    fn from(res: EncryptionResult) -> Self {
+// A missing configuration means the bucket isn't encrypted, any other error
+// means we can't tell.
+impl TryFrom<EncryptionResult> for BucketEncryption {
+    type Error = SdkError<GetBucketEncryptionError>;
+
+    fn try_from(res: EncryptionResult) -> Result<Self, Self::Error> {
         match res {
-            Ok(output) => Self::from(output),
-            Err(_)     => Self::None,
+            Ok(output) => Ok(Self::from(output)),
+            Err(error) => {
+                match error.code() {
+                    Some("ServerSideEncryptionConfigurationNotFoundError") => {
+                        Ok(Self::None)
+                    },
+                    _ => Err(error),
+                }
+            },
         }
     }
 }
 
-impl fmt::Display for BucketEncryption {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let output = match self {
+impl Findings for BucketEncryption {
+    fn findings(&self) -> Vec<Finding> {
+        let check = &SERVER_SIDE_ENCRYPTION;
+
+        let finding = match self {
             Self::Default => {
-                format!(
-                    "{} Server side encryption enabled using the default AES256 algorithm",
-                    Emoji::Info,
+                Finding::info(
+                    check,
+                    "Server side encryption enabled using the default AES256 algorithm",
                 )
             },
-            Self::Kms => {
-                format!(
-                    "{} Server side encryption enabled using KMS",
-                    Emoji::Tick,
+            Self::Kms(None) => {
+                Finding::pass(
+                    check,
+                    "Server side encryption enabled using KMS",
+                )
+            },
+            Self::Kms(Some(key)) => {
+                Finding::pass(
+                    check,
+                    format!("Server side encryption enabled using KMS key {}", key),
                 )
             },
             Self::None => {
-                format!(
-                    "{} Server side encryption is not enabled",
-                    Emoji::Cross,
+                Finding::fail(
+                    check,
+                    "Server side encryption is not enabled",
                 )
             },
             Self::Unknown(algorithm) => {
-                format!(
-                    "{} Server side encryption using unknown algorithm: {}",
-                    Emoji::Warning,
+                let message = format!(
+                    "Server side encryption using unknown algorithm: {}",
                     algorithm,
-                )
+                );
+
+                Finding::warn(check, message)
+                    .with_severity(Severity::Medium)
             },
         };
 
-        write!(f, "{}", output)
+        vec![finding]
+    }
+}
+
+// Serialized as a flat structure so that structured output has a stable
+// shape regardless of the encryption type.
+impl Serialize for BucketEncryption {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer,
+    {
+        let (algorithm, kms_key_id) = match self {
+            Self::Default            => (Some("AES256"), None),
+            Self::Kms(key)           => (Some("aws:kms"), key.as_deref()),
+            Self::None               => (None, None),
+            Self::Unknown(algorithm) => (Some(algorithm.as_str()), None),
+        };
+
+        let mut state = serializer.serialize_struct("BucketEncryption", 3)?;
+        state.serialize_field("enabled", &algorithm.is_some())?;
+        state.serialize_field("algorithm", &algorithm)?;
+        state.serialize_field("kms_key_id", &kms_key_id)?;
+        state.end()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::service_error;
     use aws_sdk_s3::types::{
         ServerSideEncryptionByDefault,
         ServerSideEncryptionConfiguration,
@@ -151,7 +208,7 @@ mod tests {
             .server_side_encryption_configuration(configuration)
             .build();
 
-        let expected = BucketEncryption::Kms;
+        let expected = BucketEncryption::Kms(Some("arn:aws:foo:bar:test".into()));
 
         let bucket_encryption: BucketEncryption = output.into();
 
@@ -185,6 +242,34 @@ mod tests {
         assert_eq!(bucket_encryption, expected);
     }
 
+    #[test]
+    fn test_serialize_kms_encryption() {
+        let encryption = BucketEncryption::Kms(Some("arn:aws:foo:bar:test".into()));
+        let expected = serde_json::json!({
+            "enabled": true,
+            "algorithm": "aws:kms",
+            "kms_key_id": "arn:aws:foo:bar:test",
+        });
+
+        let serialized = serde_json::to_value(&encryption).unwrap();
+
+        assert_eq!(serialized, expected);
+    }
+
+    #[test]
+    fn test_serialize_no_encryption() {
+        let encryption = BucketEncryption::None;
+        let expected = serde_json::json!({
+            "enabled": false,
+            "algorithm": null,
+            "kms_key_id": null,
+        });
+
+        let serialized = serde_json::to_value(&encryption).unwrap();
+
+        assert_eq!(serialized, expected);
+    }
+
     #[test]
     fn test_from_no_rules() {
         let configuration = ServerSideEncryptionConfiguration::builder()
@@ -215,4 +300,28 @@ mod tests {
 
         assert_eq!(bucket_encryption, expected);
     }
+
+    #[test]
+    fn test_try_from_not_configured() {
+        let error = service_error(
+            "ServerSideEncryptionConfigurationNotFoundError",
+            GetBucketEncryptionError::generic,
+        );
+
+        let encryption = BucketEncryption::try_from(Err(error));
+
+        assert_eq!(encryption.unwrap(), BucketEncryption::None);
+    }
+
+    #[test]
+    fn test_try_from_access_denied() {
+        let error = service_error(
+            "AccessDenied",
+            GetBucketEncryptionError::generic,
+        );
+
+        let encryption = BucketEncryption::try_from(Err(error));
+
+        assert!(encryption.is_err());
+    }
 }
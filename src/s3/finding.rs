@@ -0,0 +1,327 @@
+// Findings produced by audits
+use crate::common::Emoji;
+use crate::s3::{
+    Audit,
+    Check,
+    Severity,
+    CHECKS,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde::ser::{
+    SerializeStruct,
+    Serializer,
+};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+    Info,
+    // The check could not be evaluated
+    Error,
+}
+
+impl Status {
+    // Warnings and failures are both things that need fixing
+    pub fn is_failure(self) -> bool {
+        matches!(self, Self::Warn | Self::Fail)
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Pass  => "pass",
+            Self::Warn  => "warn",
+            Self::Fail  => "fail",
+            Self::Info  => "info",
+            Self::Error => "error",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl From<Status> for Emoji {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::Pass  => Self::Tick,
+            Status::Warn  => Self::Warning,
+            Status::Fail  => Self::Cross,
+            Status::Info  => Self::Info,
+            Status::Error => Self::Question,
+        }
+    }
+}
+
+// The outcome of a single check against a bucket
+#[derive(Debug, Eq, PartialEq)]
+pub struct Finding {
+    check:    &'static Check,
+    status:   Status,
+    severity: Severity,
+    message:  String,
+    accepted: bool,
+}
+
+impl Finding {
+    fn new<S>(check: &'static Check, status: Status, message: S) -> Self
+    where S: Into<String>,
+    {
+        Self {
+            check,
+            status,
+            severity: check.severity,
+            message:  message.into(),
+            accepted: false,
+        }
+    }
+
+    pub fn pass<S>(check: &'static Check, message: S) -> Self
+    where S: Into<String>,
+    {
+        Self::new(check, Status::Pass, message)
+    }
+
+    pub fn warn<S>(check: &'static Check, message: S) -> Self
+    where S: Into<String>,
+    {
+        Self::new(check, Status::Warn, message)
+    }
+
+    pub fn fail<S>(check: &'static Check, message: S) -> Self
+    where S: Into<String>,
+    {
+        Self::new(check, Status::Fail, message)
+    }
+
+    // Informational findings are never more than informational
+    pub fn info<S>(check: &'static Check, message: S) -> Self
+    where S: Into<String>,
+    {
+        Self::new(check, Status::Info, message)
+            .with_severity(Severity::Info)
+    }
+
+    // A check that couldn't be evaluated, usually due to an API error
+    pub fn error<S>(check: &'static Check, error: S) -> Self
+    where S: fmt::Display,
+    {
+        Self::new(check, Status::Error, format!("Could not evaluate: {}", error))
+    }
+
+    // Overrides the default severity taken from the check
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    // Marks a failure as accepted by a baseline
+    pub fn accepted(mut self) -> Self {
+        self.accepted = true;
+        self
+    }
+
+    pub fn audit(&self) -> &Audit {
+        &self.check.audit
+    }
+
+    pub fn check_id(&self) -> &'static str {
+        self.check.id
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.status == Status::Error
+    }
+
+    pub fn is_failure(&self) -> bool {
+        self.status.is_failure()
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn remediation(&self) -> &'static str {
+        self.check.remediation
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let emoji: Emoji = self.status.into();
+
+        write!(f, "{} {}", emoji, self.message)
+    }
+}
+
+impl Serialize for Finding {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Finding", 7)?;
+        state.serialize_field("check", self.check_id())?;
+        state.serialize_field("audit", &self.audit().to_string())?;
+        state.serialize_field("status", &self.status)?;
+        state.serialize_field("severity", &self.severity)?;
+        state.serialize_field("message", &self.message)?;
+        state.serialize_field("remediation", self.remediation())?;
+
+        if self.accepted {
+            state.serialize_field("accepted", &self.accepted)?;
+        }
+        else {
+            state.skip_field("accepted")?;
+        }
+
+        state.end()
+    }
+}
+
+// Controls which findings are shown in reports. The default shows everything.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FindingFilter {
+    pub only_failures: bool,
+    pub min_severity:  Severity,
+}
+
+impl FindingFilter {
+    // Checks that couldn't be evaluated are shown alongside failures
+    pub fn matches(&self, finding: &Finding) -> bool {
+        if self.only_failures && !finding.is_failure() && !finding.is_error() {
+            return false;
+        }
+
+        finding.severity() >= self.min_severity
+    }
+}
+
+// An audit that couldn't be run against a bucket
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditError {
+    pub audit:   Audit,
+    pub message: String,
+}
+
+impl AuditError {
+    pub fn new(audit: Audit, error: &anyhow::Error) -> Self {
+        Self {
+            audit,
+            message: format!("{:#}", error),
+        }
+    }
+
+    // One finding for each check in the audit
+    pub fn findings(&self) -> Vec<Finding> {
+        CHECKS.iter()
+            .filter(|check| check.audit == self.audit)
+            .map(|&check| Finding::error(check, &self.message))
+            .collect()
+    }
+}
+
+// Implemented by the result of each audit
+pub trait Findings {
+    fn findings(&self) -> Vec<Finding>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::{
+        LOGGING,
+        SERVER_SIDE_ENCRYPTION,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_info_severity() {
+        let finding = Finding::info(&SERVER_SIDE_ENCRYPTION, "Informational");
+
+        assert_eq!(finding.severity(), Severity::Info);
+        assert!(!finding.is_failure());
+    }
+
+    #[test]
+    fn test_serialize() {
+        let finding = Finding::fail(&LOGGING, "Logging is not enabled");
+        let expected = serde_json::json!({
+            "check": "s3audit/logging",
+            "audit": "logging",
+            "status": "fail",
+            "severity": "medium",
+            "message": "Logging is not enabled",
+            "remediation": "Enable server access logging to a dedicated logging bucket",
+        });
+
+        let serialized = serde_json::to_value(&finding).unwrap();
+
+        assert_eq!(serialized, expected);
+    }
+
+    #[test]
+    fn test_finding_filter() {
+        let pass = Finding::pass(&LOGGING, "Logging to logs");
+        let fail = Finding::fail(&LOGGING, "Logging is not enabled");
+        let info = Finding::info(&SERVER_SIDE_ENCRYPTION, "Informational");
+
+        let filter = FindingFilter::default();
+        assert!(filter.matches(&pass));
+        assert!(filter.matches(&fail));
+        assert!(filter.matches(&info));
+
+        let filter = FindingFilter {
+            only_failures: true,
+            min_severity:  Severity::Info,
+        };
+        assert!(!filter.matches(&pass));
+        assert!(filter.matches(&fail));
+        assert!(!filter.matches(&info));
+
+        let filter = FindingFilter {
+            only_failures: false,
+            min_severity:  Severity::Low,
+        };
+        assert!(filter.matches(&pass));
+        assert!(filter.matches(&fail));
+        assert!(!filter.matches(&info));
+
+        let filter = FindingFilter {
+            only_failures: false,
+            min_severity:  Severity::High,
+        };
+        assert!(!filter.matches(&fail));
+    }
+
+    #[test]
+    fn test_audit_error() {
+        let error = AuditError::new(Audit::Versioning, &anyhow::anyhow!("AccessDenied"));
+
+        let findings = error.findings();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].check_id(), "s3audit/versioning");
+        assert_eq!(findings[0].status(), Status::Error);
+        assert_eq!(findings[0].message(), "Could not evaluate: AccessDenied");
+
+        let filter = FindingFilter {
+            only_failures: true,
+            min_severity:  Severity::Info,
+        };
+        assert!(filter.matches(&findings[0]));
+    }
+}
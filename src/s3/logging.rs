@@ -1,9 +1,18 @@
 // Bucket logging
-use crate::common::Emoji;
+use crate::s3::{
+    Finding,
+    Findings,
+    LOGGING,
+};
 use aws_sdk_s3::operation::get_bucket_logging::GetBucketLoggingOutput;
-use std::fmt;
+use serde::Serialize;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(
+    content = "target_bucket",
+    rename_all = "lowercase",
+    tag = "status",
+)]
 pub enum BucketLogging {
     Enabled(String),
     Disabled,
@@ -17,20 +26,18 @@ impl From<GetBucketLoggingOutput> for BucketLogging {
     }
 }
 
-impl fmt::Display for BucketLogging {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let output = match self {
+impl Findings for BucketLogging {
+    fn findings(&self) -> Vec<Finding> {
+        let finding = match self {
             Self::Enabled(bucket) => {
-                let emoji = Emoji::Tick;
-                format!("{} Logging to {}", emoji, bucket)
+                Finding::pass(&LOGGING, format!("Logging to {}", bucket))
             },
             Self::Disabled => {
-                let emoji = Emoji::Cross;
-                format!("{} Logging is not enabled", emoji)
-            }
+                Finding::fail(&LOGGING, "Logging is not enabled")
+            },
         };
 
-        write!(f, "{}", output)
+        vec![finding]
     }
 }
 
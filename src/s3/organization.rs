@@ -0,0 +1,290 @@
+// Accounts in an AWS Organization, found by walking its organizational units
+// from the root.
+use anyhow::{
+    anyhow,
+    Context,
+    Error,
+    Result,
+};
+use aws_config::SdkConfig;
+use aws_sdk_organizations::client::Client as OrganizationsClient;
+use aws_sdk_organizations::types::{
+    Account,
+    AccountState,
+    AccountStatus,
+    ChildType,
+};
+use crate::s3::AccountId;
+use log::info;
+use std::fmt;
+use std::str::FromStr;
+
+// Organizational unit IDs, such as "ou-ab12-cd34ef56"
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct OuId(String);
+
+impl fmt::Display for OuId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for OuId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split('-').collect();
+
+        let valid = parts.len() == 3
+            && parts[0] == "ou"
+            && parts[1..].iter().all(|part| {
+                !part.is_empty()
+                    && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+            });
+
+        if !valid {
+            return Err(anyhow!("OU IDs look like ou-ab12-cd34ef56: {}", s));
+        }
+
+        Ok(Self(s.into()))
+    }
+}
+
+// An account is audited if it's anywhere below an included OU, or none were
+// given, and isn't anywhere below an excluded OU.
+#[derive(Debug, Default)]
+pub struct OuFilter {
+    include: Vec<OuId>,
+    exclude: Vec<OuId>,
+}
+
+impl OuFilter {
+    pub fn new(include: Vec<OuId>, exclude: Vec<OuId>) -> Self {
+        Self {
+            include,
+            exclude,
+        }
+    }
+
+    // The path is the OUs from the root down to an account's parent, so an
+    // account directly below the root has an empty path.
+    pub fn matches(&self, path: &[OuId]) -> bool {
+        let included = self.include.is_empty()
+            || path.iter().any(|ou| self.include.contains(ou));
+
+        included && !self.is_excluded(path)
+    }
+
+    // Nothing below an excluded OU can match, so it isn't walked
+    fn is_excluded(&self, path: &[OuId]) -> bool {
+        path.iter().any(|ou| self.exclude.contains(ou))
+    }
+}
+
+// Accounts that are suspended or being closed can't be audited. Older
+// responses only have the account's status.
+fn is_active(account: &Account) -> bool {
+    match account.state() {
+        Some(state) => *state == AccountState::Active,
+        None        => account.status() == Some(&AccountStatus::Active),
+    }
+}
+
+// Lists accounts from the management account, or a delegated administrator
+// account.
+pub struct Organization {
+    client: OrganizationsClient,
+}
+
+impl Organization {
+    pub fn new(config: &SdkConfig) -> Self {
+        Self {
+            client: OrganizationsClient::new(config),
+        }
+    }
+
+    // Returns the active accounts that match the filter, ordered by ID. Every
+    // OU is walked, as included OUs may be nested anywhere.
+    pub async fn accounts(&self, filter: &OuFilter) -> Result<Vec<AccountId>> {
+        let mut accounts = Vec::new();
+
+        let mut parents: Vec<(String, Vec<OuId>)> = self.roots()
+            .await?
+            .into_iter()
+            .map(|root| (root, Vec::new()))
+            .collect();
+
+        while let Some((parent, path)) = parents.pop() {
+            if filter.matches(&path) {
+                accounts.append(&mut self.accounts_for_parent(&parent).await?);
+            }
+
+            for ou in self.organizational_units(&parent).await? {
+                let mut ou_path = path.clone();
+                ou_path.push(ou.clone());
+
+                if filter.is_excluded(&ou_path) {
+                    info!("Skipping excluded OU: {}", ou);
+                    continue;
+                }
+
+                parents.push((ou.to_string(), ou_path));
+            }
+        }
+
+        accounts.sort();
+
+        Ok(accounts)
+    }
+
+    async fn roots(&self) -> Result<Vec<String>> {
+        info!("Listing organization roots");
+
+        let mut pages = self.client
+            .list_roots()
+            .into_paginator()
+            .send();
+
+        let mut roots = Vec::new();
+
+        while let Some(page) = pages.try_next().await? {
+            let ids = page.roots()
+                .iter()
+                .filter_map(|root| root.id())
+                .map(ToString::to_string);
+
+            roots.extend(ids);
+        }
+
+        Ok(roots)
+    }
+
+    async fn organizational_units(&self, parent: &str) -> Result<Vec<OuId>> {
+        info!("Listing organizational units in: {}", parent);
+
+        let mut pages = self.client
+            .list_children()
+            .parent_id(parent)
+            .child_type(ChildType::OrganizationalUnit)
+            .into_paginator()
+            .send();
+
+        let mut ous = Vec::new();
+
+        while let Some(page) = pages.try_next().await? {
+            for child in page.children() {
+                if let Some(id) = child.id() {
+                    ous.push(id.parse()?);
+                }
+            }
+        }
+
+        Ok(ous)
+    }
+
+    async fn accounts_for_parent(&self, parent: &str) -> Result<Vec<AccountId>> {
+        info!("Listing accounts in: {}", parent);
+
+        let mut pages = self.client
+            .list_accounts_for_parent()
+            .parent_id(parent)
+            .into_paginator()
+            .send();
+
+        let mut accounts = Vec::new();
+
+        while let Some(page) = pages.try_next().await? {
+            for account in page.accounts().iter().filter(|account| is_active(account)) {
+                if let Some(id) = account.id() {
+                    let id = id.parse()
+                        .with_context(|| format!("Invalid account in {}", parent))?;
+
+                    accounts.push(id);
+                }
+            }
+        }
+
+        Ok(accounts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn ou(s: &str) -> OuId {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_ou_id_from_str() {
+        assert_eq!(ou("ou-ab12-cd34ef56").to_string(), "ou-ab12-cd34ef56");
+        assert!("r-ab12".parse::<OuId>().is_err());
+        assert!("ou-ab12".parse::<OuId>().is_err());
+        assert!("ou-AB12-cd34ef56".parse::<OuId>().is_err());
+        assert!("ou--cd34ef56".parse::<OuId>().is_err());
+    }
+
+    #[test]
+    fn test_filter_default() {
+        let filter = OuFilter::default();
+
+        assert!(filter.matches(&[]));
+        assert!(filter.matches(&[ou("ou-ab12-aaaaaaaa")]));
+    }
+
+    #[test]
+    fn test_filter_include() {
+        let filter = OuFilter::new(vec![ou("ou-ab12-aaaaaaaa")], Vec::new());
+
+        // Accounts in nested OUs are included too
+        assert!(filter.matches(&[ou("ou-ab12-aaaaaaaa")]));
+        assert!(filter.matches(&[ou("ou-ab12-aaaaaaaa"), ou("ou-ab12-bbbbbbbb")]));
+
+        assert!(!filter.matches(&[]));
+        assert!(!filter.matches(&[ou("ou-ab12-bbbbbbbb")]));
+    }
+
+    #[test]
+    fn test_filter_exclude() {
+        let filter = OuFilter::new(
+            vec![ou("ou-ab12-aaaaaaaa")],
+            vec![ou("ou-ab12-bbbbbbbb")],
+        );
+
+        let excluded = [ou("ou-ab12-aaaaaaaa"), ou("ou-ab12-bbbbbbbb")];
+        let nested = [
+            ou("ou-ab12-aaaaaaaa"),
+            ou("ou-ab12-bbbbbbbb"),
+            ou("ou-ab12-cccccccc"),
+        ];
+
+        // Exclusions win over inclusions, at any depth
+        assert!(filter.matches(&[ou("ou-ab12-aaaaaaaa")]));
+        assert!(!filter.matches(&excluded));
+        assert!(!filter.matches(&nested));
+        assert!(filter.is_excluded(&nested));
+        assert!(!filter.is_excluded(&[ou("ou-ab12-aaaaaaaa")]));
+    }
+
+    #[test]
+    fn test_is_active() {
+        let active = Account::builder()
+            .state(AccountState::Active)
+            .build();
+
+        let suspended = Account::builder()
+            .state(AccountState::Suspended)
+            .status(AccountStatus::Active)
+            .build();
+
+        let status_only = Account::builder()
+            .status(AccountStatus::Active)
+            .build();
+
+        assert!(is_active(&active));
+        assert!(!is_active(&suspended));
+        assert!(is_active(&status_only));
+    }
+}
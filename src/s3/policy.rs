@@ -1,38 +1,55 @@
-// Checks if S3 policies allow wildcard entities.
+// Checks if S3 policies allow wildcard entities, or otherwise allow more than
+// they list.
 use anyhow::{
     anyhow,
     Result,
 };
-use crate::common::Emoji;
-use aws_sdk_s3::operation::get_bucket_policy::GetBucketPolicyOutput;
+use crate::s3::{
+    Finding,
+    Findings,
+    POLICY_CLOUDFRONT,
+    POLICY_WILDCARDS,
+};
+use aws_sdk_s3::error::{
+    ProvideErrorMetadata,
+    SdkError,
+};
+use aws_sdk_s3::operation::get_bucket_policy::{
+    GetBucketPolicyError,
+    GetBucketPolicyOutput,
+};
+use serde::Serialize;
+use serde::ser::{
+    SerializeStruct,
+    Serializer,
+};
 use serde_json::Value;
-use std::fmt;
 use std::convert::TryFrom;
 
 mod actions;
+mod conditions;
 mod principals;
+mod resources;
 
 use actions::Action;
+use conditions::Condition;
 use principals::Principal;
+use resources::Resource;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Serialize)]
 pub struct CloudFrontDistributions(usize);
 
-impl fmt::Display for CloudFrontDistributions {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+impl Findings for CloudFrontDistributions {
+    fn findings(&self) -> Vec<Finding> {
         let num = self.0;
 
-        if num == 0 {
-            let emoji = Emoji::Tick;
-
-            write!(
-                f,
-                "{} Bucket is not associated with any CloudFront distributions",
-                emoji,
+        let finding = if num == 0 {
+            Finding::pass(
+                &POLICY_CLOUDFRONT,
+                "Bucket is not associated with any CloudFront distributions",
             )
         }
         else {
-            let emoji = Emoji::Cross;
             let maybe_plural = if num > 1 {
                 "s"
             }
@@ -40,84 +57,266 @@ impl fmt::Display for CloudFrontDistributions {
                 ""
             };
 
-            write!(
-                f,
-                "{} Bucket is associated with {} CloudFront distribution{}",
-                emoji,
+            let message = format!(
+                "Bucket is associated with {} CloudFront distribution{}",
                 num,
                 maybe_plural,
-            )
-        }
+            );
+
+            Finding::warn(&POLICY_CLOUDFRONT, message)
+        };
+
+        vec![finding]
     }
 }
 
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug, Default, Eq, PartialEq, Serialize)]
 pub struct Wildcards(usize);
 
 impl Wildcards {
     fn add(&mut self, count: usize) {
         self.0 += count;
     }
+}
 
-    pub fn count(&self) -> usize {
-        self.0
-    }
+// A single statement from a bucket policy. The index is the statement's
+// position in the policy, used to name statements without a Sid.
+#[derive(Clone, Debug)]
+pub struct PolicyStatement {
+    index:          usize,
+    sid:            Option<String>,
+    effect:         String,
+    principals:     Principal,
+    not_principals: Principal,
+    actions:        Action,
+    not_actions:    Action,
+    resources:      Resource,
+    not_resources:  Resource,
+    condition:      Condition,
 }
 
-impl fmt::Display for Wildcards {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let num = self.0;
+impl PolicyStatement {
+    // If we're denying stuff, wildcards are fine.
+    fn is_deny(&self) -> bool {
+        self.effect == "Deny"
+    }
 
-        if num == 0 {
-            let emoji = Emoji::Tick;
+    // How the statement is referred to in findings
+    fn name(&self) -> String {
+        match &self.sid {
+            Some(sid) => format!("Statement \"{}\"", sid),
+            None      => format!("Statement {} (no Sid)", self.index + 1),
+        }
+    }
 
-            write!(
-                f,
-                "{} Bucket policy doesn't allow a wildcard entity",
-                emoji,
-            )
+    // Describes each part of the statement that allows more than it lists,
+    // quoting the offending element. Deny statements can't allow anything.
+    fn problems(&self) -> Vec<String> {
+        if self.is_deny() {
+            return Vec::new();
         }
-        else {
-            let emoji = Emoji::Cross;
-            let maybe_plural = if num > 1 {
-                "s"
-            }
-            else {
-                ""
-            };
 
-            write!(
-                f,
-                "{} Bucket has {} statement{} with wildcard entities",
-                emoji,
-                num,
-                maybe_plural,
-            )
+        let mut problems: Vec<String> = self.principals
+            .wildcard_arns()
+            .map(|arn| format!("allows any principal with Principal \"{}\"", arn))
+            .collect();
+
+        if !self.not_principals.is_empty() {
+            problems.push(format!(
+                "allows every principal except NotPrincipal {}",
+                quoted(self.not_principals.arns()),
+            ));
+        }
+
+        for name in self.actions.wildcard_names() {
+            problems.push(format!("allows wildcard Action \"{}\"", name));
+        }
+
+        if !self.not_actions.is_empty() {
+            problems.push(format!(
+                "allows every action except NotAction {}",
+                quoted(self.not_actions.names()),
+            ));
         }
+
+        for arn in self.resources.wildcard_arns() {
+            problems.push(format!("allows every resource with Resource \"{}\"", arn));
+        }
+
+        if !self.not_resources.is_empty() {
+            problems.push(format!(
+                "allows every resource except NotResource {}",
+                quoted(self.not_resources.arns()),
+            ));
+        }
+
+        problems
+    }
+
+    // A finding for each problem with the statement. A Condition may limit
+    // the statement to trusted callers, such as a VPC endpoint, so those are
+    // warnings to be checked rather than failures.
+    pub fn findings(&self) -> Vec<Finding> {
+        let name = self.name();
+        let keys = self.condition.keys();
+
+        self.problems()
+            .into_iter()
+            .map(|problem| {
+                if keys.is_empty() {
+                    let message = format!("{} {}", name, problem);
+
+                    Finding::fail(&POLICY_WILDCARDS, message)
+                }
+                else {
+                    let message = format!(
+                        "{} {}, limited by a Condition on {}",
+                        name,
+                        problem,
+                        keys.join(", "),
+                    );
+
+                    Finding::warn(&POLICY_WILDCARDS, message)
+                }
+            })
+            .collect()
+    }
+
+    pub fn cloudfront_distributions(&self) -> usize {
+        if self.is_deny() {
+            return 0;
+        }
+
+        self.principals.cloudfront_distributions()
+    }
+
+    // Returns the wildcard entities that this statement allows
+    pub fn wildcards(&self) -> Vec<&String> {
+        if self.is_deny() {
+            return Vec::new();
+        }
+
+        self.actions.wildcard_names()
+            .chain(self.principals.wildcard_arns())
+            .collect()
     }
 }
 
-#[derive(Debug, Default)]
+impl Serialize for PolicyStatement {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("PolicyStatement", 10)?;
+        state.serialize_field("sid", &self.sid)?;
+        state.serialize_field("effect", &self.effect)?;
+        state.serialize_field("actions", &self.actions)?;
+        state.serialize_field("not_actions", &self.not_actions)?;
+        state.serialize_field("principals", &self.principals)?;
+        state.serialize_field("not_principals", &self.not_principals)?;
+        state.serialize_field("resources", &self.resources)?;
+        state.serialize_field("not_resources", &self.not_resources)?;
+        state.serialize_field("condition", &self.condition)?;
+        state.serialize_field("wildcards", &self.wildcards())?;
+        state.end()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
 pub struct BucketPolicy {
-    actions: Action,
-    principals: Principal,
+    statements: Vec<PolicyStatement>,
 }
 
+// Type alias to avoid long lines below
+type PolicyResult = Result<GetBucketPolicyOutput, SdkError<GetBucketPolicyError>>;
+
 impl BucketPolicy {
+    // A bucket without a policy is reported as an error by the API, any
+    // other error means we can't tell.
+    pub fn from_result(res: PolicyResult) -> Result<Option<Self>> {
+        let output = match res {
+            Ok(output) => output,
+            Err(error) => {
+                match error.code() {
+                    Some("NoSuchBucketPolicy") => return Ok(None),
+                    _                          => return Err(error.into()),
+                }
+            },
+        };
+
+        // Didn't get 404 but no policy supplied
+        if output.policy.is_none() {
+            return Ok(None);
+        }
+
+        let bucket_policy: Self = output.try_into()?;
+
+        Ok(Some(bucket_policy))
+    }
+
     pub fn cloudfront_distributions(&self) -> CloudFrontDistributions {
-        CloudFrontDistributions(self.principals.cloudfront_distributions())
+        let num = self.statements.iter()
+            .map(PolicyStatement::cloudfront_distributions)
+            .sum();
+
+        CloudFrontDistributions(num)
     }
 
     pub fn wildcards(&self) -> Wildcards {
         let mut wildcards = Wildcards::default();
 
-        wildcards.add(self.actions.wildcards());
-        wildcards.add(self.principals.wildcards());
+        for statement in &self.statements {
+            wildcards.add(statement.wildcards().len());
+        }
 
         wildcards
     }
 }
 
+// Each statement has its own findings, so that it's clear which need fixing
+impl Findings for BucketPolicy {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings: Vec<Finding> = self.statements.iter()
+            .flat_map(PolicyStatement::findings)
+            .collect();
+
+        if findings.is_empty() {
+            findings.push(Finding::pass(
+                &POLICY_WILDCARDS,
+                "Bucket policy doesn't allow a wildcard entity",
+            ));
+        }
+
+        findings.append(&mut self.cloudfront_distributions().findings());
+
+        findings
+    }
+}
+
+// Entries quoted and separated by commas
+fn quoted<'a, I>(entries: I) -> String
+where I: Iterator<Item = &'a String>,
+{
+    entries
+        .map(|entry| format!("\"{}\"", entry))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl Serialize for BucketPolicy {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("BucketPolicy", 3)?;
+        state.serialize_field("wildcards", &self.wildcards())?;
+        state.serialize_field(
+            "cloudfront_distributions",
+            &self.cloudfront_distributions(),
+        )?;
+        state.serialize_field("statements", &self.statements)?;
+        state.end()
+    }
+}
+
 impl TryFrom<GetBucketPolicyOutput> for BucketPolicy {
     type Error = anyhow::Error;
 
@@ -130,41 +329,65 @@ impl TryFrom<GetBucketPolicyOutput> for BucketPolicy {
         // We expect that AWS will always give us a well formed JSON policy
         let jv: Value = serde_json::from_str(policy)?;
 
-        // The policy will contain an array of statements.
-        let statements = &jv["Statement"];
+        // The policy will contain an array of statements, or a single
+        // statement on its own.
+        let statements = match &jv["Statement"] {
+            Value::Array(statements)     => statements.iter().collect(),
+            statement @ Value::Object(_) => vec![statement],
+            _                            => {
+                return Err(anyhow!("Bucket policy has no Statement element"));
+            },
+        };
 
-        let mut actions = Action::default();
-        let mut principals = Principal::default();
+        let mut policy_statements = Vec::new();
 
-        let statements_array = statements.as_array()
-            .expect("Bucket policy has no Statements element");
+        for (index, statement) in statements.into_iter().enumerate() {
+            // Policies MUST have an effect.
+            let Some(effect) = statement["Effect"].as_str() else {
+                return Err(anyhow!(
+                    "Bucket policy statement {} does not have an Effect",
+                    index + 1,
+                ));
+            };
 
-        for statement in statements_array {
-            // Policies MUST have an effect. This should never fail.
-            let effect = statement["Effect"]
+            // Statement IDs are optional, and may be blank.
+            let sid = statement["Sid"]
                 .as_str()
-                .expect("Bucket policy statement does not have an explicit Effect");
-
-            // If we're denying stuff, wildcards are fine and we can proceed
-            // to the next statement.
-            if effect == "Deny" {
-                continue
-            }
+                .filter(|sid| !sid.is_empty())
+                .map(String::from);
 
             // Process the actions.
-            let action = &statement["Action"];
-            let action: Action = action.into();
-            actions.append(action);
+            let actions: Action = (&statement["Action"]).into();
+            let not_actions: Action = (&statement["NotAction"]).into();
 
             // Process the principals.
             let principal = &statement["Principal"];
-            let principal: Principal = principal.into();
-            principals.append(principal);
+            let principals: Principal = principal.into();
+            let not_principals: Principal = (&statement["NotPrincipal"]).into();
+
+            // Process the resources and conditions.
+            let resources: Resource = (&statement["Resource"]).into();
+            let not_resources: Resource = (&statement["NotResource"]).into();
+            let condition: Condition = (&statement["Condition"]).into();
+
+            let policy_statement = PolicyStatement {
+                index,
+                sid,
+                principals,
+                not_principals,
+                actions,
+                not_actions,
+                resources,
+                not_resources,
+                condition,
+                effect: effect.into(),
+            };
+
+            policy_statements.push(policy_statement);
         }
 
         Ok(Self {
-            actions,
-            principals,
+            statements: policy_statements,
         })
     }
 }
@@ -172,19 +395,24 @@ impl TryFrom<GetBucketPolicyOutput> for BucketPolicy {
 #[derive(Debug, Default)]
 pub struct NoBucketPolicy;
 
-impl fmt::Display for NoBucketPolicy {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} No bucket policy set", Emoji::Info)
+impl Findings for NoBucketPolicy {
+    fn findings(&self) -> Vec<Finding> {
+        let finding = Finding::info(&POLICY_WILDCARDS, "No bucket policy set");
+
+        vec![finding]
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::service_error;
+    use crate::s3::Status;
     use pretty_assertions::assert_eq;
     use serde_json::json;
     use std::convert::TryInto;
 
+    #[allow(clippy::manual_map)]
     fn policy(value: Option<serde_json::Value>) -> BucketPolicy {
         let policy = match value {
             None         => None,
@@ -368,6 +596,243 @@ mod tests {
         assert_eq!(wildcards, expected);
     }
 
+    #[test]
+    fn test_policy_serialize_statements() {
+        let json = json!({
+            "Version": "2012-10-17",
+            "Statement": [
+                {
+                    "Sid": "AllowEveryone",
+                    "Effect": "Allow",
+                    "Action": [
+                        "s3:GetObject",
+                        "s3:*",
+                    ],
+                    "Resource": "arn:aws:s3:::s3audit-rs-example-bucket/*",
+                    "Principal": {
+                        "AWS": "*",
+                    },
+                },
+                {
+                    "Effect": "Deny",
+                    "Action": "*",
+                    "Resource": "arn:aws:s3:::s3audit-rs-example-bucket/*",
+                    "Principal": "*",
+                },
+            ],
+        });
+
+        let policy = policy(Some(json));
+        let expected = json!({
+            "wildcards": 2,
+            "cloudfront_distributions": 0,
+            "statements": [
+                {
+                    "sid": "AllowEveryone",
+                    "effect": "Allow",
+                    "actions": [
+                        "s3:GetObject",
+                        "s3:*",
+                    ],
+                    "not_actions": [],
+                    "principals": [
+                        "*",
+                    ],
+                    "not_principals": [],
+                    "resources": [
+                        "arn:aws:s3:::s3audit-rs-example-bucket/*",
+                    ],
+                    "not_resources": [],
+                    "condition": {},
+                    "wildcards": [
+                        "s3:*",
+                        "*",
+                    ],
+                },
+                {
+                    "sid": null,
+                    "effect": "Deny",
+                    "actions": [
+                        "*",
+                    ],
+                    "not_actions": [],
+                    "principals": [
+                        "*",
+                    ],
+                    "not_principals": [],
+                    "resources": [
+                        "arn:aws:s3:::s3audit-rs-example-bucket/*",
+                    ],
+                    "not_resources": [],
+                    "condition": {},
+                    "wildcards": [],
+                },
+            ],
+        });
+
+        let serialized = serde_json::to_value(&policy).unwrap();
+
+        assert_eq!(serialized, expected);
+    }
+
+    #[test]
+    fn test_policy_statement_findings() {
+        let json = json!({
+            "Version": "2012-10-17",
+            "Statement": [
+                {
+                    "Sid": "AllowEveryone",
+                    "Effect": "Allow",
+                    "Action": [
+                        "s3:GetObject",
+                        "s3:*",
+                    ],
+                    "Resource": "arn:aws:s3:::s3audit-rs-example-bucket/*",
+                    "Principal": "*",
+                },
+                {
+                    "Effect": "Allow",
+                    "NotAction": [
+                        "s3:DeleteBucket",
+                        "s3:DeleteObject",
+                    ],
+                    "NotResource": "arn:aws:s3:::s3audit-rs-example-bucket/private/*",
+                    "Principal": {
+                        "AWS": "arn:aws:iam::123456789012:root",
+                    },
+                },
+                {
+                    "Sid": "AllowVpcEndpoint",
+                    "Effect": "Allow",
+                    "Action": "s3:GetObject",
+                    "Resource": "arn:aws:s3:::s3audit-rs-example-bucket/*",
+                    "Principal": "*",
+                    "Condition": {
+                        "StringEquals": {
+                            "aws:SourceVpce": "vpce-1a2b3c4d",
+                        },
+                    },
+                },
+                {
+                    "Effect": "Allow",
+                    "Action": "s3:GetObject",
+                    "Resource": "*",
+                    "NotPrincipal": {
+                        "AWS": "arn:aws:iam::123456789012:role/Admin",
+                    },
+                },
+            ],
+        });
+
+        let policy = policy(Some(json));
+        let findings = policy.findings();
+
+        let findings: Vec<(Status, &str)> = findings.iter()
+            .filter(|finding| finding.check_id() == POLICY_WILDCARDS.id)
+            .map(|finding| (finding.status(), finding.message()))
+            .collect();
+
+        let expected = vec![
+            (
+                Status::Fail,
+                r#"Statement "AllowEveryone" allows any principal with Principal "*""#,
+            ),
+            (
+                Status::Fail,
+                r#"Statement "AllowEveryone" allows wildcard Action "s3:*""#,
+            ),
+            (
+                Status::Fail,
+                r#"Statement 2 (no Sid) allows every action except NotAction "s3:DeleteBucket", "s3:DeleteObject""#,
+            ),
+            (
+                Status::Fail,
+                r#"Statement 2 (no Sid) allows every resource except NotResource "arn:aws:s3:::s3audit-rs-example-bucket/private/*""#,
+            ),
+            (
+                Status::Warn,
+                r#"Statement "AllowVpcEndpoint" allows any principal with Principal "*", limited by a Condition on aws:SourceVpce"#,
+            ),
+            (
+                Status::Fail,
+                r#"Statement 4 (no Sid) allows every principal except NotPrincipal "arn:aws:iam::123456789012:role/Admin""#,
+            ),
+            (
+                Status::Fail,
+                r#"Statement 4 (no Sid) allows every resource with Resource "*""#,
+            ),
+        ];
+
+        assert_eq!(findings, expected);
+    }
+
+    #[test]
+    fn test_policy_single_statement() {
+        // A statement doesn't need to be in an array
+        let json = json!({
+            "Version": "2012-10-17",
+            "Statement": {
+                "Sid": "AllowEveryone",
+                "Effect": "Allow",
+                "Action": "s3:GetObject",
+                "Resource": "arn:aws:s3:::s3audit-rs-example-bucket/*",
+                "Principal": "*",
+            },
+        });
+
+        let policy = policy(Some(json));
+
+        let statuses: Vec<Status> = policy.findings()
+            .iter()
+            .filter(|finding| finding.check_id() == POLICY_WILDCARDS.id)
+            .map(Finding::status)
+            .collect();
+
+        assert_eq!(statuses, vec![Status::Fail]);
+    }
+
+    #[test]
+    fn test_policy_invalid() {
+        let tests = vec![
+            json!({"Version": "2012-10-17"}),
+            json!({"Statement": "s3:*"}),
+            json!({"Statement": [{"Action": "s3:*", "Resource": "*"}]}),
+        ];
+
+        for json in tests {
+            let output = GetBucketPolicyOutput::builder()
+                .policy(json.to_string())
+                .build();
+
+            assert!(BucketPolicy::try_from(output).is_err());
+        }
+    }
+
+    #[test]
+    fn test_policy_no_statement_findings() {
+        let json = json!({
+            "Version": "2012-10-17",
+            "Statement": [
+                {
+                    "Effect": "Deny",
+                    "NotAction": "s3:GetObject",
+                    "Resource": "arn:aws:s3:::s3audit-rs-example-bucket/*",
+                    "Principal": "*",
+                },
+            ],
+        });
+
+        let policy = policy(Some(json));
+
+        let statuses: Vec<Status> = policy.findings()
+            .iter()
+            .filter(|finding| finding.check_id() == POLICY_WILDCARDS.id)
+            .map(Finding::status)
+            .collect();
+
+        assert_eq!(statuses, vec![Status::Pass]);
+    }
+
     #[test]
     fn test_policy_some_service_principal() {
         let json = json!({
@@ -390,4 +855,25 @@ mod tests {
 
         assert_eq!(wildcards, expected);
     }
+
+    #[test]
+    fn test_from_result_no_policy() {
+        let error = service_error(
+            "NoSuchBucketPolicy",
+            GetBucketPolicyError::generic,
+        );
+
+        let policy = BucketPolicy::from_result(Err(error));
+
+        assert!(policy.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_from_result_access_denied() {
+        let error = service_error("AccessDenied", GetBucketPolicyError::generic);
+
+        let policy = BucketPolicy::from_result(Err(error));
+
+        assert!(policy.is_err());
+    }
 }
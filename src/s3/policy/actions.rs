@@ -1,22 +1,32 @@
 // Checks if S3 policies allow wildcard entities on Actions
+use serde::Serialize;
 use serde_json::Value;
 
 const WILDCARD: &str = "*";
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct Action(Vec<String>);
 
 impl Action {
-    pub fn append(&mut self, mut other: Self) {
-        self.0.append(&mut other.0);
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 
-    pub fn wildcards(&self) -> usize {
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.0.iter()
+    }
+
+    // Returns the action names containing wildcards
+    pub fn wildcard_names(&self) -> impl Iterator<Item = &String> {
         // Wildcards could appear anywhere in the name
         // eg. "*", "s3:*", "iam:*AccessKey*"
         self.0.iter()
             .filter(|&name| name.contains(WILDCARD))
-            .count()
+    }
+
+    #[cfg(test)]
+    pub fn wildcards(&self) -> usize {
+        self.wildcard_names().count()
     }
 }
 
@@ -0,0 +1,80 @@
+// Conditions limiting when an S3 policy statement applies
+use serde::Serialize;
+use serde_json::{
+    Map,
+    Value,
+};
+
+// Operators, such as "StringEquals", each holding condition keys and values.
+// Statements without a Condition have an empty map.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(transparent)]
+pub struct Condition(Map<String, Value>);
+
+impl Condition {
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // Returns the condition keys, such as "aws:SourceVpce", sorted and
+    // without duplicates
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.0.values()
+            .filter_map(Value::as_object)
+            .flat_map(Map::keys)
+            .map(String::as_str)
+            .collect();
+
+        keys.sort_unstable();
+        keys.dedup();
+
+        keys
+    }
+}
+
+// Takes a Value representing the Condition entry in a Bucket Policy
+impl From<&Value> for Condition {
+    fn from(value: &Value) -> Self {
+        match value {
+            // "Condition": {
+            //   "StringEquals": {
+            //     "aws:SourceVpce": "vpce-1a2b3c4d"
+            //   }
+            // }
+            Value::Object(operators) => Self(operators.clone()),
+            _                        => Self::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_policy_condition() {
+        let policy = json!({
+            "Condition": {
+                "StringEquals": {
+                    "aws:SourceVpce": "vpce-1a2b3c4d",
+                    "aws:PrincipalOrgID": "o-a1b2c3d4e5",
+                },
+                "StringLike": {
+                    "aws:SourceVpce": "vpce-*",
+                },
+            },
+        });
+
+        let condition: Condition = (&policy["Condition"]).into();
+        let expected = vec!["aws:PrincipalOrgID", "aws:SourceVpce"];
+
+        assert_eq!(condition.keys(), expected);
+
+        let condition: Condition = (&policy["Missing"]).into();
+
+        assert!(condition.is_empty());
+    }
+}
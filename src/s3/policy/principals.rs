@@ -1,16 +1,21 @@
 // Checks if S3 policies allow wildcard entities.
 use log::debug;
+use serde::Serialize;
 use serde_json::Value;
 
 const CLOUDFRONT_OAI: &str = "arn:aws:iam::cloudfront:user/CloudFront Origin Access Identity ";
 const WILDCARD: &str = "*";
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct Principal(Vec<String>);
 
 impl Principal {
-    pub fn append(&mut self, mut other: Self) {
-        self.0.append(&mut other.0);
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn arns(&self) -> impl Iterator<Item = &String> {
+        self.0.iter()
     }
 
     pub fn cloudfront_distributions(&self) -> usize {
@@ -19,10 +24,15 @@ impl Principal {
             .count()
     }
 
-    pub fn wildcards(&self) -> usize {
+    // Returns the principals that are wildcards
+    pub fn wildcard_arns(&self) -> impl Iterator<Item = &String> {
         self.0.iter()
             .filter(|&arn| arn == WILDCARD)
-            .count()
+    }
+
+    #[cfg(test)]
+    pub fn wildcards(&self) -> usize {
+        self.wildcard_arns().count()
     }
 }
 
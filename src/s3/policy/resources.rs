@@ -0,0 +1,84 @@
+// Resources named by S3 policy statements
+use serde::Serialize;
+use serde_json::Value;
+
+const WILDCARD: &str = "*";
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Resource(Vec<String>);
+
+impl Resource {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn arns(&self) -> impl Iterator<Item = &String> {
+        self.0.iter()
+    }
+
+    // Returns the resources that are wildcards
+    pub fn wildcard_arns(&self) -> impl Iterator<Item = &String> {
+        self.0.iter()
+            .filter(|&arn| arn == WILDCARD)
+    }
+}
+
+// Takes a Value representing the Resource or NotResource entry in a Bucket
+// Policy and returns a Vec of the ARNs wrapped in a Resource struct.
+impl From<&Value> for Resource {
+    fn from(value: &Value) -> Self {
+        let arns = match value {
+            // "Resource": "arn:aws:s3:::bucket/*"
+            Value::String(arn) => {
+                vec![
+                    String::from(arn),
+                ]
+            },
+            // "Resource": [
+            //   "arn:aws:s3:::bucket",
+            //   "arn:aws:s3:::bucket/*",
+            // ]
+            Value::Array(arns) => {
+                arns.iter()
+                    .filter_map(Value::as_str)
+                    .map(String::from)
+                    .collect()
+            },
+            _ => Vec::new(),
+        };
+
+        Self(arns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_policy_resource() {
+        let policy = json!({
+            "Resource": "arn:aws:s3:::bucket/*",
+            "NotResource": [
+                "arn:aws:s3:::bucket",
+                "arn:aws:s3:::bucket/*",
+            ],
+        });
+
+        let resource: Resource = (&policy["Resource"]).into();
+        let expected = vec!["arn:aws:s3:::bucket/*"];
+
+        assert_eq!(resource.0, expected);
+
+        let resource: Resource = (&policy["NotResource"]).into();
+        let expected = vec!["arn:aws:s3:::bucket", "arn:aws:s3:::bucket/*"];
+
+        assert_eq!(resource.0, expected);
+
+        let resource: Resource = (&policy["Missing"]).into();
+
+        assert!(resource.is_empty());
+    }
+}
@@ -0,0 +1,198 @@
+// Progress of a scan, shown on stderr while buckets are being audited
+use crate::s3::Report;
+use std::io::{
+    self,
+    IsTerminal,
+    Write,
+};
+use std::sync::{
+    Mutex,
+    MutexGuard,
+};
+use std::time::{
+    Duration,
+    Instant,
+};
+
+// Clears the current line of the terminal
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+#[derive(Debug, Default)]
+struct Counts {
+    discovered: usize,
+    audited:    usize,
+    failed:     usize,
+    // When the first bucket started being audited, used for the ETA
+    started:    Option<Instant>,
+}
+
+// Progress is only shown when stderr is a terminal, so that it doesn't end
+// up in logs. Failed buckets are those with audits that couldn't be run.
+#[derive(Debug, Default)]
+pub struct Progress {
+    enabled: bool,
+    counts:  Mutex<Counts>,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self {
+            enabled: io::stderr().is_terminal(),
+            counts:  Mutex::default(),
+        }
+    }
+
+    // A bucket was found that will be audited
+    pub fn discovered(&self) {
+        self.update(|counts| counts.discovered += 1);
+    }
+
+    // Buckets are about to be audited
+    pub fn auditing(&self) {
+        self.update(|counts| {
+            counts.started.get_or_insert_with(Instant::now);
+        });
+    }
+
+    // A bucket has been audited
+    pub fn audited(&self, report: &Report) {
+        self.update(|counts| {
+            counts.audited += 1;
+
+            if report.has_errors() {
+                counts.failed += 1;
+            }
+        });
+    }
+
+    // Runs a function that writes to the terminal, with the progress line
+    // hidden while it runs.
+    pub fn suspend<F, T>(&self, f: F) -> T
+    where F: FnOnce() -> T,
+    {
+        let counts = self.lock();
+
+        self.clear();
+        let result = f();
+        self.draw(&counts);
+
+        result
+    }
+
+    // Removes the progress line, so that the reports can be written
+    pub fn finish(&self) {
+        let _counts = self.lock();
+
+        self.clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Counts> {
+        self.counts
+            .lock()
+            .expect("progress poisoned")
+    }
+
+    fn update<F>(&self, f: F)
+    where F: FnOnce(&mut Counts),
+    {
+        let mut counts = self.lock();
+
+        f(&mut counts);
+        self.draw(&counts);
+    }
+
+    // Progress is best effort, so failing to write it isn't an error
+    fn clear(&self) {
+        if self.enabled {
+            let _ = write!(io::stderr(), "{}", CLEAR_LINE);
+        }
+    }
+
+    fn draw(&self, counts: &Counts) {
+        if self.enabled {
+            let _ = write!(io::stderr(), "{}{}", CLEAR_LINE, status(counts));
+        }
+    }
+}
+
+// The progress line, such as
+// "12 buckets discovered, 4 audited, 1 failed, ETA 1m 30s"
+fn status(counts: &Counts) -> String {
+    let mut status = format!(
+        "{} buckets discovered, {} audited, {} failed",
+        counts.discovered,
+        counts.audited,
+        counts.failed,
+    );
+
+    // The rate so far gives the time left for the remaining buckets
+    if let Some(started) = counts.started {
+        if counts.audited > 0 && counts.audited < counts.discovered {
+            let remaining = counts.discovered - counts.audited;
+            let per_bucket = started.elapsed() / counts.audited as u32;
+
+            let eta = per_bucket * remaining as u32;
+
+            status.push_str(&format!(", ETA {}", format_duration(eta)));
+        }
+    }
+
+    status
+}
+
+// Durations to the second, such as "1h 2m", "2m 10s" or "5s"
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+
+    match (seconds / 3600, (seconds % 3600) / 60, seconds % 60) {
+        (0, 0, seconds)       => format!("{}s", seconds),
+        (0, minutes, seconds) => format!("{}m {}s", minutes, seconds),
+        (hours, minutes, _)   => format!("{}h {}m", hours, minutes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_format_duration() {
+        let tests = vec![
+            (5,    "5s"),
+            (130,  "2m 10s"),
+            (3720, "1h 2m"),
+        ];
+
+        for (seconds, expected) in tests {
+            let duration = Duration::from_secs(seconds);
+
+            assert_eq!(format_duration(duration), expected);
+        }
+    }
+
+    #[test]
+    fn test_status() {
+        let counts = Counts {
+            discovered: 12,
+            audited:    0,
+            failed:     0,
+            started:    None,
+        };
+
+        assert_eq!(status(&counts), "12 buckets discovered, 0 audited, 0 failed");
+
+        // Half way through after 10 seconds leaves 10 seconds to go
+        let counts = Counts {
+            discovered: 12,
+            audited:    6,
+            failed:     1,
+            started:    Instant::now().checked_sub(Duration::from_millis(10_500)),
+        };
+
+        assert_eq!(
+            status(&counts),
+            "12 buckets discovered, 6 audited, 1 failed, ETA 10s",
+        );
+    }
+}
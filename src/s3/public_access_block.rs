@@ -1,10 +1,29 @@
 // Implements a nice enum for expressing public access block status
-use crate::common::Emoji;
-use aws_sdk_s3::operation::get_public_access_block::GetPublicAccessBlockOutput;
-use std::fmt;
+use crate::s3::{
+    Check,
+    Finding,
+    Findings,
+    BLOCK_PUBLIC_ACLS,
+    BLOCK_PUBLIC_POLICY,
+    IGNORE_PUBLIC_ACLS,
+    RESTRICT_PUBLIC_BUCKETS,
+};
+use aws_sdk_s3::error::{
+    ProvideErrorMetadata,
+    SdkError,
+};
+use aws_sdk_s3::operation::get_public_access_block::{
+    GetPublicAccessBlockError,
+    GetPublicAccessBlockOutput,
+};
+use serde::ser::{
+    Serialize,
+    SerializeMap,
+    Serializer,
+};
 use std::ops::Deref;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum PublicAccessBlockType {
     BlockPublicAcls(bool),
     BlockPublicPolicy(bool),
@@ -12,32 +31,62 @@ pub enum PublicAccessBlockType {
     RestrictPublicBuckets(bool),
 }
 
-impl fmt::Display for PublicAccessBlockType {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let output = match *self {
-            Self::BlockPublicAcls(b) => {
-                let emoji: Emoji = b.into();
-                format!("{} BlockPublicAcls is set to {}", emoji, b)
-            },
-            Self::BlockPublicPolicy(b) => {
-                let emoji: Emoji = b.into();
-                format!("{} BlockPublicPolicy is set to {}", emoji, b)
-            },
-            Self::IgnorePublicAcls(b) => {
-                let emoji: Emoji = b.into();
-                format!("{} IgnorePublicAcls is set to {}", emoji, b)
-            },
-            Self::RestrictPublicBuckets(b) => {
-                let emoji: Emoji = b.into();
-                format!("{} RestrictPublicBuckets is set to {}", emoji, b)
-            },
+impl PublicAccessBlockType {
+    // Name of the flag, as used in structured output
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Self::BlockPublicAcls(_)       => "block_public_acls",
+            Self::BlockPublicPolicy(_)     => "block_public_policy",
+            Self::IgnorePublicAcls(_)      => "ignore_public_acls",
+            Self::RestrictPublicBuckets(_) => "restrict_public_buckets",
+        }
+    }
+
+    // The check made against this flag
+    pub fn check(&self) -> &'static Check {
+        match *self {
+            Self::BlockPublicAcls(_)       => &BLOCK_PUBLIC_ACLS,
+            Self::BlockPublicPolicy(_)     => &BLOCK_PUBLIC_POLICY,
+            Self::IgnorePublicAcls(_)      => &IGNORE_PUBLIC_ACLS,
+            Self::RestrictPublicBuckets(_) => &RESTRICT_PUBLIC_BUCKETS,
+        }
+    }
+
+    // Whether or not the flag is set
+    pub fn enabled(&self) -> bool {
+        match *self {
+            Self::BlockPublicAcls(b)
+            | Self::BlockPublicPolicy(b)
+            | Self::IgnorePublicAcls(b)
+            | Self::RestrictPublicBuckets(b) => b,
+        }
+    }
+}
+
+impl Findings for PublicAccessBlockType {
+    fn findings(&self) -> Vec<Finding> {
+        let name = match *self {
+            Self::BlockPublicAcls(_)       => "BlockPublicAcls",
+            Self::BlockPublicPolicy(_)     => "BlockPublicPolicy",
+            Self::IgnorePublicAcls(_)      => "IgnorePublicAcls",
+            Self::RestrictPublicBuckets(_) => "RestrictPublicBuckets",
         };
 
-        write!(f, "{}", output)
+        let enabled = self.enabled();
+        let message = format!("{} is set to {}", name, enabled);
+
+        let finding = if enabled {
+            Finding::pass(self.check(), message)
+        }
+        else {
+            Finding::fail(self.check(), message)
+        };
+
+        vec![finding]
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PublicAccessBlock(Vec<PublicAccessBlockType>);
 
 impl Default for PublicAccessBlock {
@@ -74,6 +123,55 @@ impl From<GetPublicAccessBlockOutput> for PublicAccessBlock {
     }
 }
 
+// Type alias to avoid long lines in the TryFrom impl
+type PublicAccessBlockResult = Result<
+    GetPublicAccessBlockOutput,
+    SdkError<GetPublicAccessBlockError>,
+>;
+
+// A missing configuration means none of the flags are set, any other error
+// means we can't tell.
+impl TryFrom<PublicAccessBlockResult> for PublicAccessBlock {
+    type Error = SdkError<GetPublicAccessBlockError>;
+
+    fn try_from(res: PublicAccessBlockResult) -> Result<Self, Self::Error> {
+        match res {
+            Ok(output) => Ok(Self::from(output)),
+            Err(error) => {
+                match error.code() {
+                    Some("NoSuchPublicAccessBlockConfiguration") => {
+                        Ok(Self::default())
+                    },
+                    _ => Err(error),
+                }
+            },
+        }
+    }
+}
+
+impl Findings for PublicAccessBlock {
+    fn findings(&self) -> Vec<Finding> {
+        self.0.iter()
+            .flat_map(PublicAccessBlockType::findings)
+            .collect()
+    }
+}
+
+// Serialized as a map of flag name to flag state.
+impl Serialize for PublicAccessBlock {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+
+        for block in &self.0 {
+            map.serialize_entry(block.name(), &block.enabled())?;
+        }
+
+        map.end()
+    }
+}
+
 // Allows us to directly iterate over the struct inner.
 impl Deref for PublicAccessBlock {
     type Target = Vec<PublicAccessBlockType>;
@@ -86,6 +184,7 @@ impl Deref for PublicAccessBlock {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::service_error;
     use aws_sdk_s3::types::PublicAccessBlockConfiguration;
 
     #[test]
@@ -112,4 +211,49 @@ mod tests {
 
         assert_eq!(public_access_block, expected)
     }
+
+    #[test]
+    fn test_serialize() {
+        let public_access_block = PublicAccessBlock(vec![
+            PublicAccessBlockType::BlockPublicAcls(true),
+            PublicAccessBlockType::BlockPublicPolicy(false),
+            PublicAccessBlockType::IgnorePublicAcls(true),
+            PublicAccessBlockType::RestrictPublicBuckets(false),
+        ]);
+
+        let expected = serde_json::json!({
+            "block_public_acls": true,
+            "block_public_policy": false,
+            "ignore_public_acls": true,
+            "restrict_public_buckets": false,
+        });
+
+        let serialized = serde_json::to_value(&public_access_block).unwrap();
+
+        assert_eq!(serialized, expected)
+    }
+
+    #[test]
+    fn test_try_from_not_configured() {
+        let error = service_error(
+            "NoSuchPublicAccessBlockConfiguration",
+            GetPublicAccessBlockError::generic,
+        );
+
+        let public_access_block = PublicAccessBlock::try_from(Err(error));
+
+        assert_eq!(public_access_block.unwrap(), PublicAccessBlock::default());
+    }
+
+    #[test]
+    fn test_try_from_access_denied() {
+        let error = service_error(
+            "AccessDenied",
+            GetPublicAccessBlockError::generic,
+        );
+
+        let public_access_block = PublicAccessBlock::try_from(Err(error));
+
+        assert!(public_access_block.is_err());
+    }
 }
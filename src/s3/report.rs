@@ -1,108 +1,496 @@
 // Bucket reporting in various formats
-use anyhow::Result;
+use anyhow::{
+    anyhow,
+    Result,
+};
 use colored::Colorize;
 use crate::common::Emoji;
 use crate::s3::{
+    AcceptedFindings,
+    AccountId,
+    Audit,
+    AuditError,
     BucketAcl,
     BucketEncryption,
     BucketLogging,
     BucketPolicy,
+    BucketTags,
     BucketVersioning,
     BucketWebsite,
+    Finding,
+    FindingFilter,
+    Findings,
     NoBucketPolicy,
     PublicAccessBlock,
+    Severity,
+    Status,
 };
-use std::io;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
 
 mod csv_output;
+mod html_output;
+mod json_output;
+mod junit_output;
+mod markdown_output;
+mod report_group;
+mod report_output;
+mod report_sink;
 mod report_type;
+mod sarif_output;
+mod summary;
 
 pub use csv_output::*;
+pub use html_output::*;
+pub use json_output::*;
+pub use junit_output::*;
+pub use markdown_output::*;
+pub use report_group::*;
+pub use report_output::*;
+pub use report_sink::*;
 pub use report_type::*;
+pub use sarif_output::*;
+pub use summary::*;
 
 #[derive(Debug, Default)]
 pub struct ReportOptions {
-    pub output_type: ReportType,
+    pub outputs: Vec<ReportOutput>,
 }
 
-#[derive(Debug)]
+impl ReportOptions {
+    // Outputs without their own path are written to the default path, if one
+    // is given, otherwise they go to stdout.
+    pub fn new(
+        outputs: Vec<ReportOutput>,
+        default_path: Option<PathBuf>,
+    ) -> Result<Self> {
+        let outputs: Vec<ReportOutput> = outputs.into_iter()
+            .map(|mut output| {
+                if output.path.is_none() {
+                    output.path.clone_from(&default_path);
+                }
+
+                output
+            })
+            .collect();
+
+        // Interleaving several formats in one place would make all of them
+        // unusable.
+        for (i, output) in outputs.iter().enumerate() {
+            let duplicate = outputs[..i].iter()
+                .any(|other| other.path == output.path);
+
+            if duplicate {
+                let destination = output.path
+                    .as_ref()
+                    .map_or_else(|| "stdout".into(), |p| p.display().to_string());
+
+                return Err(anyhow!(
+                    "Multiple report formats would be written to {}",
+                    destination,
+                ));
+            }
+        }
+
+        Ok(Self {
+            outputs,
+        })
+    }
+
+    // Grouped reports are written one after another, which only makes sense
+    // for text on stdout. Other formats need a path for each group's file.
+    pub fn check_grouping(&self) -> Result<()> {
+        let ungroupable = self.outputs.iter()
+            .any(|output| {
+                output.path.is_none()
+                    && !matches!(output.output_type, ReportType::Text)
+            });
+
+        if ungroupable {
+            return Err(anyhow!(
+                "Only text reports can be grouped on stdout, give formats a path",
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Opens a sink for every output, creating any files
+    pub fn open(&self) -> Result<Vec<Box<dyn ReportSink>>> {
+        self.outputs.iter()
+            .map(|output| {
+                open_sink(&output.output_type, output.path.as_deref(), None)
+            })
+            .collect()
+    }
+}
+
+// Audits that weren't run are omitted from structured output, a bucket
+// without a policy has its policy serialized as null. Audits that couldn't be
+// run are listed in errors.
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct Report {
     pub name:                String,
+    // The account the bucket was found in, when auditing several accounts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account:             Option<AccountId>,
+    // None if the bucket's region couldn't be found
+    pub region:              Option<String>,
+    #[serde(skip_serializing_if = "BucketTags::is_empty")]
+    pub tags:                BucketTags,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub acl:                 Option<BucketAcl>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub encryption:          Option<BucketEncryption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub logging:             Option<BucketLogging>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub policy:              Option<Option<BucketPolicy>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub public_access_block: Option<PublicAccessBlock>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub versioning:          Option<BucketVersioning>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub website:             Option<BucketWebsite>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors:              Vec<AuditError>,
+    // Which findings are shown, this doesn't affect the exit status or the
+    // summary.
+    #[serde(skip)]
+    pub filter:              FindingFilter,
+    // Failures accepted by a baseline
+    #[serde(skip)]
+    pub accepted:            AcceptedFindings,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Reports(Vec<Report>);
 
 impl Report {
-    // CSV output
-    pub fn csv<W>(&self, writer: &mut csv::Writer<W>) -> Result<()>
-    where W: ::std::io::Write,
-    {
-        let output: CsvOutput = self.into();
-        writer.serialize(output)?;
+    // A report on a bucket that hasn't been audited yet
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
 
-        Ok(())
+    // The example bucket used throughout the tests, in a known region with
+    // nothing audited. Tests fill in the audits they need.
+    #[cfg(test)]
+    pub fn example() -> Self {
+        Self {
+            region: Some("eu-west-1".into()),
+            ..Self::new("s3audit-rs-example-bucket")
+        }
     }
 
-    // Simple text output
-    pub fn text(&self) {
-        println!("  {} {}", Emoji::Arrow, self.name.bold().blue());
+    // The ARN of the bucket being reported on
+    pub fn arn(&self) -> String {
+        format!("arn:aws:s3:::{}", self.name)
+    }
 
-        // Public access configuration
-        if let Some(blocks) = &self.public_access_block {
-            println!("    {} Bucket public access configuration", Emoji::Arrow);
+    // The bucket's region, for display
+    pub fn region_name(&self) -> &str {
+        self.region.as_deref().unwrap_or("unknown")
+    }
+
+    // Returns the audits that were run against this bucket, based on the
+    // information we gathered. Audits that couldn't be evaluated are
+    // included.
+    pub fn audits(&self) -> Vec<Audit> {
+        let mut audits = Vec::new();
 
-            for block in blocks.iter() {
-                println!("      {}", block);
+        let mut push = |audit: Audit, ran: bool| {
+            if ran || self.has_error(&audit) {
+                audits.push(audit);
             }
+        };
+
+        push(Audit::PublicAccessBlocks, self.public_access_block.is_some());
+        push(Audit::ServerSideEncryption, self.encryption.is_some());
+
+        // MFA Delete and Versioning both come from the Versioning API
+        push(Audit::Versioning, self.versioning.is_some());
+        push(Audit::MfaDelete, self.versioning.is_some());
+
+        push(Audit::Website, self.website.is_some());
+
+        // CloudFront distributions are found via the bucket policy
+        push(Audit::Policy, self.policy.is_some());
+        push(Audit::Cloudfront, self.policy.is_some());
+
+        push(Audit::Acl, self.acl.is_some());
+        push(Audit::Logging, self.logging.is_some());
+
+        audits
+    }
+
+    // Returns true if the given audit couldn't be evaluated
+    pub fn has_error(&self, audit: &Audit) -> bool {
+        self.errors.iter()
+            .any(|error| error.audit == *audit)
+    }
+
+    // Returns the findings for audits that couldn't be evaluated
+    fn error_findings(&self, audits: &[Audit]) -> Vec<Finding> {
+        self.errors.iter()
+            .filter(|error| audits.contains(&error.audit))
+            .flat_map(AuditError::findings)
+            .collect()
+    }
+
+    // Returns the findings allowed by the filter, in the order they are
+    // displayed.
+    pub fn findings(&self) -> Vec<Finding> {
+        self.all_findings()
+            .into_iter()
+            .filter(|finding| self.filter.matches(finding))
+            .collect()
+    }
+
+    // Returns the findings from every audit that was run, ignoring the filter.
+    // Failures accepted by a baseline are never returned.
+    pub fn all_findings(&self) -> Vec<Finding> {
+        self.audit_findings()
+            .into_iter()
+            .filter(|finding| !self.is_accepted(finding))
+            .collect()
+    }
+
+    // Returns the failures accepted by a baseline, allowed by the filter.
+    // These are only written to JSON reports, so that a report can be used
+    // as the next run's baseline.
+    pub fn accepted_findings(&self) -> Vec<Finding> {
+        self.audit_findings()
+            .into_iter()
+            .filter(|finding| self.is_accepted(finding))
+            .filter(|finding| self.filter.matches(finding))
+            .map(Finding::accepted)
+            .collect()
+    }
+
+    fn is_accepted(&self, finding: &Finding) -> bool {
+        finding.is_failure() && self.accepted.contains(finding)
+    }
+
+    // Returns the findings from every audit that was run, including accepted
+    // failures.
+    fn audit_findings(&self) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        match &self.public_access_block {
+            Some(blocks) => findings.append(&mut blocks.findings()),
+            None         => {
+                let audits = [Audit::PublicAccessBlocks];
+                findings.append(&mut self.error_findings(&audits));
+            },
         }
 
-        // Encryption
-        if let Some(encryption) = &self.encryption {
-            println!("    {}", encryption);
+        match &self.encryption {
+            Some(encryption) => findings.append(&mut encryption.findings()),
+            None             => {
+                let audits = [Audit::ServerSideEncryption];
+                findings.append(&mut self.error_findings(&audits));
+            },
         }
 
-        // Versioning and MFA Delete
-        if let Some(versioning) = &self.versioning {
-            println!("    {}", versioning.versioning());
-            println!("    {}", versioning.mfa_delete());
+        match &self.versioning {
+            Some(versioning) => findings.append(&mut versioning.findings()),
+            None             => {
+                let audits = [Audit::Versioning, Audit::MfaDelete];
+                findings.append(&mut self.error_findings(&audits));
+            },
         }
 
-        // Static website hosting
-        if let Some(website) = &self.website {
-            println!("    {}", website);
+        match &self.website {
+            Some(website) => findings.append(&mut website.findings()),
+            None          => {
+                let audits = [Audit::Website];
+                findings.append(&mut self.error_findings(&audits));
+            },
         }
 
-        // Bucket policy
-        if let Some(policy) = &self.policy {
-            match &policy {
-                None => {
-                    println!("    {}", NoBucketPolicy { });
-                },
-                Some(policy) => {
-                    println!("    {}", policy.wildcards());
-                    println!("    {}", policy.cloudfront_distributions());
-                },
+        match &self.policy {
+            Some(None)         => findings.append(&mut NoBucketPolicy.findings()),
+            Some(Some(policy)) => findings.append(&mut policy.findings()),
+            None               => {
+                let audits = [Audit::Policy, Audit::Cloudfront];
+                findings.append(&mut self.error_findings(&audits));
+            },
+        }
+
+        match &self.acl {
+            Some(acl) => findings.append(&mut acl.findings()),
+            None      => {
+                let audits = [Audit::Acl];
+                findings.append(&mut self.error_findings(&audits));
+            },
+        }
+
+        match &self.logging {
+            Some(logging) => findings.append(&mut logging.findings()),
+            None          => {
+                let audits = [Audit::Logging];
+                findings.append(&mut self.error_findings(&audits));
+            },
+        }
+
+        findings
+    }
+
+    // Returns the overall status of a single audit, which is the worst status
+    // of any of its findings. Returns None if the audit wasn't run.
+    pub fn audit_status(&self, audit: &Audit) -> Option<Status> {
+        let statuses: Vec<Status> = self.all_findings()
+            .iter()
+            .filter(|finding| finding.audit() == audit)
+            .map(Finding::status)
+            .collect();
+
+        let worst = [
+            Status::Fail,
+            Status::Error,
+            Status::Warn,
+            Status::Info,
+            Status::Pass,
+        ];
+
+        worst.into_iter()
+            .find(|status| statuses.contains(status))
+    }
+
+    // Returns the findings for checks that this bucket failed, allowed by the
+    // filter
+    pub fn failures(&self) -> Vec<Finding> {
+        self.findings()
+            .into_iter()
+            .filter(Finding::is_failure)
+            .collect()
+    }
+
+    // Returns the findings for every check that this bucket failed
+    pub fn all_failures(&self) -> Vec<Finding> {
+        self.all_findings()
+            .into_iter()
+            .filter(Finding::is_failure)
+            .collect()
+    }
+
+    // Returns true if any audit couldn't be evaluated
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    // Buckets with nothing left to show after filtering are left out of
+    // reports entirely.
+    pub fn is_visible(&self) -> bool {
+        !self.findings().is_empty()
+    }
+
+    // JSON reports also include accepted failures, so a bucket whose only
+    // failures were accepted is still written to them.
+    pub fn is_visible_in_json(&self) -> bool {
+        self.is_visible() || !self.accepted_findings().is_empty()
+    }
+
+    // A bucket passes if it has no failures at or above the given severity
+    pub fn passed(&self, threshold: Severity) -> bool {
+        self.all_failures()
+            .iter()
+            .all(|failure| failure.severity() < threshold)
+    }
+
+    // CSV output
+    pub fn csv<W>(&self, writer: &mut csv::Writer<W>) -> Result<()>
+    where W: Write,
+    {
+        for finding in &self.findings() {
+            let output = CsvOutput::new(self, finding);
+            writer.serialize(output)?;
+        }
+
+        Ok(())
+    }
+
+    // NDJSON output, a single line of JSON for this bucket
+    pub fn ndjson<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        let output = JsonReport::from(self);
+
+        serde_json::to_writer(&mut *writer, &output)?;
+        writeln!(writer)?;
+
+        Ok(())
+    }
+
+    // Simple text output
+    pub fn text<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        let location = match &self.account {
+            Some(account) => format!("{}, {}", account, self.region_name()),
+            None          => self.region_name().to_string(),
+        };
+
+        writeln!(
+            writer,
+            "  {} {} ({})",
+            Emoji::Arrow,
+            self.name.bold().blue(),
+            location,
+        )?;
+
+        // Public access configuration is nested under its own heading
+        let (blocks, findings): (Vec<Finding>, Vec<Finding>) = self.findings()
+            .into_iter()
+            .partition(|finding| {
+                *finding.audit() == Audit::PublicAccessBlocks
+            });
+
+        if !blocks.is_empty() {
+            writeln!(
+                writer,
+                "    {} Bucket public access configuration",
+                Emoji::Arrow,
+            )?;
+
+            for finding in &blocks {
+                writeln!(writer, "      {}", finding)?;
             }
         }
 
-        // Bucket ACL
-        if let Some(acl) = &self.acl {
-            println!("    {}", acl);
+        for finding in &findings {
+            writeln!(writer, "    {}", finding)?;
         }
 
-        // Bucket logging
-        if let Some(logging) = &self.logging {
-            println!("    {}", logging);
+        Ok(())
+    }
+}
+
+// Returns every audit that was run against any of the reports, in the order
+// they were first seen.
+pub fn audits_run(reports: &[Report]) -> Vec<Audit> {
+    let mut audits = Vec::new();
+
+    for report in reports {
+        for audit in report.audits() {
+            if !audits.contains(&audit) {
+                audits.push(audit);
+            }
         }
     }
+
+    audits
+}
+
+// Returns the reports that have findings to show
+pub fn visible_reports(reports: &[Report]) -> impl Iterator<Item = &Report> {
+    reports.iter()
+        .filter(|report| report.is_visible())
 }
 
 impl Reports {
@@ -110,35 +498,487 @@ impl Reports {
         Self(reports)
     }
 
-    pub fn output(&self, options: &ReportOptions) -> Result<()> {
-        match options.output_type {
-            ReportType::Csv  => self.csv()?,
-            ReportType::Text => self.text(),
+    // Reports arrive in the order their buckets finish being audited, so
+    // they're sorted by account, then bucket name, before being written
+    pub fn sort(&mut self) {
+        self.0.sort_by(|a, b| {
+            a.account.cmp(&b.account)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+    }
+
+    // Splits the reports up by a value taken from each report. Groups are
+    // sorted by value, with reports that don't have a value last.
+    fn group_by<F>(self, key: &str, value: F) -> Vec<ReportGroup>
+    where F: Fn(&Report) -> Option<String>,
+    {
+        let mut grouped: BTreeMap<String, Vec<Report>> = BTreeMap::new();
+        let mut ungrouped = Vec::new();
+
+        for report in self.0 {
+            match value(&report) {
+                Some(value) => {
+                    grouped.entry(value)
+                        .or_default()
+                        .push(report);
+                },
+                None => ungrouped.push(report),
+            }
         }
 
-        Ok(())
+        let mut groups: Vec<ReportGroup> = grouped.into_iter()
+            .map(|(value, reports)| {
+                ReportGroup::new(key, Some(value), Self(reports))
+            })
+            .collect();
+
+        if !ungrouped.is_empty() {
+            groups.push(ReportGroup::new(key, None, Self(ungrouped)));
+        }
+
+        groups
     }
 
-    // CSV output
-    // Wrapping the report CSV method and passing a writer here is necessary,
-    // otherwise we end up with duplicate headers when dealing with multiple
-    // buckets.
-    pub fn csv(&self) -> Result<()> {
-        let mut writer = csv::Writer::from_writer(io::stdout());
+    // Splits the reports up by the value of the given tag
+    pub fn group_by_tag(self, tag: &str) -> Vec<ReportGroup> {
+        self.group_by(tag, |report| report.tags.get(tag).cloned())
+    }
+
+    // Splits the reports up by the account the buckets were found in
+    pub fn group_by_account(self) -> Vec<ReportGroup> {
+        self.group_by("account", |report| {
+            report.account.as_ref().map(ToString::to_string)
+        })
+    }
+
+    // Writes every report to the sink, as though they had been streamed
+    pub fn write_to(&self, sink: &mut dyn ReportSink) -> Result<()> {
+        sink.begin()?;
 
         for report in &self.0 {
-            report.csv(&mut writer)?;
+            sink.bucket(report)?;
         }
 
-        writer.flush()?;
+        sink.end()
+    }
+
+    // HTML output
+    // A standalone HTML document, suitable for sending to auditors.
+    pub fn html<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        let output = HtmlOutput::new(&self.0);
+        output.write(writer)?;
 
         Ok(())
     }
 
-    // Text output
-    pub fn text(&self) {
-        for report in &self.0 {
-            report.text();
-        }
+    // JSON output
+    // A single JSON document covering every bucket in the run.
+    pub fn json<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        let output = JsonOutput::new(&self.0);
+
+        serde_json::to_writer_pretty(&mut *writer, &output)?;
+        writeln!(writer)?;
+
+        Ok(())
+    }
+
+    // JUnit XML output
+    // Each bucket is a test suite, and each audit a test case.
+    pub fn junit<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        let output: JunitOutput = self.0.as_slice().into();
+        output.write(writer)?;
+
+        Ok(())
+    }
+
+    // Markdown output
+    // Suitable for pasting into change tickets.
+    pub fn markdown<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        let output = MarkdownOutput::new(&self.0);
+        output.write(writer)?;
+
+        Ok(())
+    }
+
+    // SARIF output
+    // Each failed check becomes a result located at the bucket ARN.
+    pub fn sarif<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        let output: SarifOutput = self.0.as_slice().into();
+
+        serde_json::to_writer_pretty(&mut *writer, &output)?;
+        writeln!(writer)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::Baseline;
+    use aws_sdk_s3::operation::get_bucket_policy::GetBucketPolicyOutput;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn test_findings() {
+        let report = Report {
+            acl:                 Some(BucketAcl::Public),
+            encryption:          Some(BucketEncryption::Default),
+            logging:             Some(BucketLogging::Enabled("logs".into())),
+            policy:              Some(None),
+            public_access_block: Some(PublicAccessBlock::default()),
+            website:             Some(BucketWebsite::Disabled),
+            ..Report::example()
+        };
+
+        let check_ids: Vec<&str> = report.findings()
+            .iter()
+            .map(Finding::check_id)
+            .collect();
+
+        let expected = vec![
+            "s3audit/block-public-acls",
+            "s3audit/block-public-policy",
+            "s3audit/ignore-public-acls",
+            "s3audit/restrict-public-buckets",
+            "s3audit/server-side-encryption",
+            "s3audit/website",
+            "s3audit/policy-wildcards",
+            "s3audit/acl-public-access",
+            "s3audit/logging",
+        ];
+
+        assert_eq!(check_ids, expected);
+    }
+
+    #[test]
+    fn test_failures() {
+        let report = Report {
+            acl:                 Some(BucketAcl::Public),
+            encryption:          Some(BucketEncryption::Default),
+            logging:             Some(BucketLogging::Enabled("logs".into())),
+            policy:              Some(None),
+            public_access_block: Some(PublicAccessBlock::default()),
+            website:             Some(BucketWebsite::Disabled),
+            ..Report::example()
+        };
+
+        let check_ids: Vec<&str> = report.failures()
+            .iter()
+            .map(Finding::check_id)
+            .collect();
+
+        let expected = vec![
+            "s3audit/block-public-acls",
+            "s3audit/block-public-policy",
+            "s3audit/ignore-public-acls",
+            "s3audit/restrict-public-buckets",
+            "s3audit/acl-public-access",
+        ];
+
+        assert_eq!(check_ids, expected);
+    }
+
+    #[test]
+    fn test_report_options_default_path() {
+        let outputs = vec![
+            "text".parse().unwrap(),
+            "json=report.json".parse().unwrap(),
+        ];
+
+        let options = ReportOptions::new(outputs, Some("report.txt".into()))
+            .unwrap();
+
+        let paths: Vec<Option<PathBuf>> = options.outputs
+            .into_iter()
+            .map(|output| output.path)
+            .collect();
+
+        let expected = vec![
+            Some(PathBuf::from("report.txt")),
+            Some(PathBuf::from("report.json")),
+        ];
+
+        assert_eq!(paths, expected);
+    }
+
+    #[test]
+    fn test_report_options_duplicate_destination() {
+        let outputs = vec![
+            "text".parse().unwrap(),
+            "json".parse().unwrap(),
+        ];
+
+        let options = ReportOptions::new(outputs, None);
+
+        assert!(options.is_err());
+    }
+
+    #[test]
+    fn test_report_options_check_grouping() {
+        let outputs = vec![
+            "text".parse().unwrap(),
+            "json=report.json".parse().unwrap(),
+        ];
+
+        let options = ReportOptions::new(outputs, None).unwrap();
+
+        assert!(options.check_grouping().is_ok());
+
+        let outputs = vec!["json".parse().unwrap()];
+        let options = ReportOptions::new(outputs, None).unwrap();
+
+        assert!(options.check_grouping().is_err());
+    }
+
+    #[test]
+    fn test_passed() {
+        let mut report = Report {
+            acl:                 Some(BucketAcl::Public),
+            encryption:          Some(BucketEncryption::Default),
+            logging:             Some(BucketLogging::Enabled("logs".into())),
+            policy:              Some(None),
+            public_access_block: Some(PublicAccessBlock::default()),
+            website:             Some(BucketWebsite::Disabled),
+            ..Report::example()
+        };
+
+        assert!(!report.passed(Severity::Critical));
+
+        report.acl = Some(BucketAcl::Private);
+
+        assert!(report.passed(Severity::Critical));
+        assert!(report.passed(Severity::High));
+        assert!(!report.passed(Severity::Medium));
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut report = Report {
+            acl:                 Some(BucketAcl::Public),
+            encryption:          Some(BucketEncryption::Default),
+            logging:             Some(BucketLogging::Enabled("logs".into())),
+            policy:              Some(None),
+            public_access_block: Some(PublicAccessBlock::default()),
+            website:             Some(BucketWebsite::Disabled),
+            ..Report::example()
+        };
+
+        report.filter = FindingFilter {
+            only_failures: true,
+            min_severity:  Severity::High,
+        };
+
+        let check_ids: Vec<&str> = report.findings()
+            .iter()
+            .map(Finding::check_id)
+            .collect();
+
+        assert_eq!(check_ids, vec!["s3audit/acl-public-access"]);
+
+        // The filter only affects what is shown
+        assert_eq!(report.all_failures().len(), 5);
+        assert!(!report.passed(Severity::Medium));
+
+        report.acl = Some(BucketAcl::Private);
+
+        assert!(!report.is_visible());
+    }
+
+    #[test]
+    fn test_errors() {
+        let report = Report {
+            errors: vec![
+                AuditError::new(Audit::Acl, &anyhow!("AccessDenied")),
+            ],
+            ..Report::example()
+        };
+
+        let finding = report.findings()
+            .into_iter()
+            .find(|finding| *finding.audit() == Audit::Acl)
+            .unwrap();
+
+        assert_eq!(finding.status(), Status::Error);
+        assert_eq!(finding.message(), "Could not evaluate: AccessDenied");
+        assert!(report.audits().contains(&Audit::Acl));
+        assert_eq!(report.audit_status(&Audit::Acl), Some(Status::Error));
+        assert!(report.has_errors());
+    }
+
+    #[test]
+    fn test_accepted() {
+        let mut report = Report {
+            acl:                 Some(BucketAcl::Public),
+            encryption:          Some(BucketEncryption::Default),
+            logging:             Some(BucketLogging::Enabled("logs".into())),
+            policy:              Some(None),
+            public_access_block: Some(PublicAccessBlock::default()),
+            website:             Some(BucketWebsite::Disabled),
+            ..Report::example()
+        };
+
+        report.accepted.insert("s3audit/acl-public-access", None);
+
+        assert!(report.passed(Severity::Critical));
+        assert!(!report.passed(Severity::Medium));
+
+        // Only failures are left out
+        report.accepted.insert("s3audit/logging", None);
+
+        assert_eq!(report.findings().len(), 8);
+    }
+
+    #[test]
+    fn test_accepted_json() {
+        let mut report = Report {
+            acl:                 Some(BucketAcl::Public),
+            encryption:          Some(BucketEncryption::Default),
+            logging:             Some(BucketLogging::Enabled("logs".into())),
+            policy:              Some(None),
+            public_access_block: Some(PublicAccessBlock::default()),
+            website:             Some(BucketWebsite::Disabled),
+            ..Report::example()
+        };
+
+        report.accepted.insert("s3audit/acl-public-access", None);
+
+        let failure = report.accepted_findings()
+            .pop()
+            .unwrap();
+
+        let mut output = Vec::new();
+        Reports::new(vec![report]).json(&mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+
+        // Accepted failures are kept, so the report can be the next
+        // baseline
+        assert!(output.contains(r#""accepted": true"#));
+
+        let baseline = Baseline::parse(&output).unwrap();
+
+        assert!(baseline.accepted("s3audit-rs-example-bucket").contains(&failure));
+    }
+
+    #[test]
+    fn test_accepted_json_only() {
+        let mut report = Report {
+            acl: Some(BucketAcl::Public),
+            ..Report::example()
+        };
+
+        report.accepted.insert("s3audit/acl-public-access", None);
+
+        let mut output = Vec::new();
+        Reports::new(vec![report]).json(&mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+
+        // The bucket has nothing else to show, but is kept so that the
+        // accepted failure is in the next baseline
+        assert!(output.contains(r#""accepted": true"#));
+    }
+
+    #[test]
+    fn test_accepted_policy_statement() {
+        let statement = |sid: &str| {
+            json!({
+                "Sid": sid,
+                "Effect": "Allow",
+                "Action": "s3:*",
+                "Resource": "arn:aws:s3:::s3audit-rs-example-bucket/*",
+                "Principal": {
+                    "AWS": "arn:aws:iam::111111111111:root",
+                },
+            })
+        };
+
+        let policy = json!({
+            "Version": "2012-10-17",
+            "Statement": [
+                statement("Old"),
+                statement("New"),
+            ],
+        });
+
+        let output = GetBucketPolicyOutput::builder()
+            .policy(policy.to_string())
+            .build();
+
+        let mut report = Report {
+            policy: Some(Some(output.try_into().unwrap())),
+            ..Report::example()
+        };
+
+        report.accepted.insert(
+            "s3audit/policy-wildcards",
+            Some(r#"Statement "Old" allows wildcard Action "s3:*""#),
+        );
+
+        let messages: Vec<String> = report.failures()
+            .iter()
+            .filter(|finding| finding.check_id() == "s3audit/policy-wildcards")
+            .map(|finding| finding.message().to_string())
+            .collect();
+
+        // A statement added since the baseline is still reported
+        assert_eq!(
+            messages,
+            vec![r#"Statement "New" allows wildcard Action "s3:*""#],
+        );
+    }
+
+    #[test]
+    fn test_group_by_tag() {
+        let tagged = |name: &str, owner: Option<&str>| {
+            let mut report = Report {
+                name: name.into(),
+                ..Report::example()
+            };
+
+            if let Some(owner) = owner {
+                report.tags = BucketTags::from([("owner", owner)]);
+            }
+
+            report
+        };
+
+        let reports = Reports::new(vec![
+            tagged("b-1", Some("team-b")),
+            tagged("none", None),
+            tagged("a-1", Some("team-a")),
+            tagged("b-2", Some("team-b")),
+        ]);
+
+        let groups: Vec<(String, Vec<String>)> = reports.group_by_tag("owner")
+            .into_iter()
+            .map(|group| {
+                let names = group.reports.0.iter()
+                    .map(|report| report.name.to_string())
+                    .collect();
+
+                (group.heading(), names)
+            })
+            .collect();
+
+        let expected = vec![
+            ("owner=team-a".into(), vec!["a-1".into()]),
+            ("owner=team-b".into(), vec!["b-1".into(), "b-2".into()]),
+            ("owner (untagged)".into(), vec!["none".into()]),
+        ];
+
+        assert_eq!(groups, expected);
     }
 }
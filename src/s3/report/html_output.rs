@@ -0,0 +1,257 @@
+// HtmlOutput
+use anyhow::Result;
+use crate::common::escape_xml;
+use crate::s3::Finding;
+use std::io::Write;
+use super::{
+    audits_run,
+    visible_reports,
+    Report,
+    Summary,
+};
+
+// Kept inline so that the report is a single standalone file
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; }
+.pass { background-color: #dff0d8; }
+.warn { background-color: #fcf8e3; }
+.fail { background-color: #f2dede; }
+.info { background-color: #d9edf7; }
+.error { background-color: #e2e3e5; }
+";
+
+pub struct HtmlOutput<'a> {
+    reports: &'a [Report],
+}
+
+impl<'a> HtmlOutput<'a> {
+    pub fn new(reports: &'a [Report]) -> Self {
+        Self {
+            reports,
+        }
+    }
+
+    pub fn write<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        writeln!(writer, "<!DOCTYPE html>")?;
+        writeln!(writer, "<html>")?;
+        writeln!(writer, "<head>")?;
+        writeln!(writer, r#"<meta charset="utf-8">"#)?;
+        writeln!(writer, "<title>S3 Audit Report</title>")?;
+        writeln!(writer, "<style>\n{}</style>", STYLE)?;
+        writeln!(writer, "</head>")?;
+        writeln!(writer, "<body>")?;
+        writeln!(writer, "<h1>S3 Audit Report</h1>")?;
+
+        self.write_summary(writer)?;
+
+        writeln!(writer, "<h2>Buckets</h2>")?;
+
+        for report in visible_reports(self.reports) {
+            write_bucket(writer, report)?;
+        }
+
+        write_totals(writer, &Summary::new(self.reports))?;
+
+        writeln!(writer, "</body>")?;
+        writeln!(writer, "</html>")?;
+
+        Ok(())
+    }
+
+    // A table of buckets against audits
+    fn write_summary<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        let audits = audits_run(self.reports);
+
+        writeln!(writer, "<h2>Summary</h2>")?;
+        writeln!(writer, "<table>")?;
+        write!(writer, "<tr><th>Bucket</th><th>Region</th>")?;
+
+        for audit in &audits {
+            write!(writer, "<th>{}</th>", audit)?;
+        }
+
+        writeln!(writer, "</tr>")?;
+
+        for report in visible_reports(self.reports) {
+            write!(
+                writer,
+                r##"<tr><td><a href="#{}">{}</a></td><td>{}</td>"##,
+                escape_xml(&report.name),
+                escape_xml(&report.name),
+                escape_xml(report.region_name()),
+            )?;
+
+            for audit in &audits {
+                match report.audit_status(audit) {
+                    None => write!(writer, "<td>-</td>")?,
+                    Some(status) => {
+                        write!(
+                            writer,
+                            r#"<td class="{}">{}</td>"#,
+                            status,
+                            status,
+                        )?;
+                    },
+                }
+            }
+
+            writeln!(writer, "</tr>")?;
+        }
+
+        writeln!(writer, "</table>")?;
+
+        Ok(())
+    }
+}
+
+// A table of the findings for a single bucket
+fn write_bucket<W>(writer: &mut W, report: &Report) -> Result<()>
+where W: Write,
+{
+    writeln!(
+        writer,
+        r#"<h3 id="{}">{}</h3>"#,
+        escape_xml(&report.name),
+        escape_xml(&report.name),
+    )?;
+    writeln!(writer, "<table>")?;
+    writeln!(
+        writer,
+        "<tr><th>Status</th><th>Severity</th><th>Check</th>\
+         <th>Finding</th><th>Remediation</th></tr>",
+    )?;
+
+    for finding in &report.findings() {
+        write_finding(writer, finding)?;
+    }
+
+    writeln!(writer, "</table>")?;
+
+    Ok(())
+}
+
+// The account level roll-up
+fn write_totals<W>(writer: &mut W, summary: &Summary) -> Result<()>
+where W: Write,
+{
+    writeln!(writer, "<h2>Totals</h2>")?;
+    writeln!(writer, "<ul>")?;
+    writeln!(writer, "<li>Buckets scanned: {}</li>", summary.buckets)?;
+    writeln!(
+        writer,
+        "<li>Compliance: {:.1}% ({} of {} buckets have no failures or errors)</li>",
+        summary.compliance,
+        summary.compliant_buckets,
+        summary.buckets,
+    )?;
+    writeln!(
+        writer,
+        "<li>Buckets with audit errors: {}</li>",
+        summary.errored_buckets,
+    )?;
+    writeln!(writer, "</ul>")?;
+
+    if !summary.failures_by_audit.is_empty() {
+        writeln!(writer, "<table>")?;
+        writeln!(writer, "<tr><th>Audit</th><th>Failing buckets</th></tr>")?;
+
+        for audit in &summary.failures_by_audit {
+            writeln!(
+                writer,
+                "<tr><td>{}</td><td>{}</td></tr>",
+                audit.audit,
+                audit.failures,
+            )?;
+        }
+
+        writeln!(writer, "</table>")?;
+    }
+
+    if !summary.most_failures.is_empty() {
+        writeln!(writer, "<table>")?;
+        writeln!(writer, "<tr><th>Bucket</th><th>Failures</th></tr>")?;
+
+        for bucket in &summary.most_failures {
+            writeln!(
+                writer,
+                r##"<tr><td><a href="#{}">{}</a></td><td>{}</td></tr>"##,
+                escape_xml(&bucket.name),
+                escape_xml(&bucket.name),
+                bucket.failures,
+            )?;
+        }
+
+        writeln!(writer, "</table>")?;
+    }
+
+    Ok(())
+}
+
+fn write_finding<W>(writer: &mut W, finding: &Finding) -> Result<()>
+where W: Write,
+{
+    // Remediation is only interesting for things that need fixing
+    let remediation = if finding.is_failure() {
+        escape_xml(finding.remediation())
+    }
+    else {
+        String::new()
+    };
+
+    writeln!(
+        writer,
+        r#"<tr class="{}"><td>{}</td><td>{}</td><td><code>{}</code></td><td>{}</td><td>{}</td></tr>"#,
+        finding.status(),
+        finding.status(),
+        finding.severity(),
+        finding.check_id(),
+        escape_xml(finding.message()),
+        remediation,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::BucketAcl;
+
+    #[test]
+    fn test_html_output() {
+        let reports = vec![
+            Report {
+                acl: Some(BucketAcl::Public),
+                ..Report::example()
+            },
+        ];
+
+        let output = HtmlOutput::new(&reports);
+        let mut buffer = Vec::new();
+        output.write(&mut buffer).unwrap();
+
+        let html = String::from_utf8(buffer).unwrap();
+
+        assert!(html.contains(
+            r##"<tr><th>Bucket</th><th>Region</th><th>acl</th></tr>"##,
+        ));
+        assert!(html.contains(
+            r##"<tr><td><a href="#s3audit-rs-example-bucket">s3audit-rs-example-bucket</a></td><td>eu-west-1</td><td class="fail">fail</td></tr>"##,
+        ));
+        assert!(html.contains(
+            r#"<h3 id="s3audit-rs-example-bucket">s3audit-rs-example-bucket</h3>"#,
+        ));
+        assert!(html.contains(
+            "<td>Bucket allows public access via ACL</td>",
+        ));
+        assert!(html.contains(
+            "<li>Compliance: 0.0% (0 of 1 buckets have no failures or errors)</li>",
+        ));
+    }
+}
@@ -0,0 +1,64 @@
+// JsonOutput
+use crate::s3::Finding;
+use serde::Serialize;
+use super::{
+    Report,
+    Summary,
+};
+
+// A single bucket's report, along with the findings derived from it.
+// Failures accepted by a baseline are included, marked as accepted.
+#[derive(Serialize)]
+pub struct JsonReport<'a> {
+    #[serde(flatten)]
+    report:   &'a Report,
+    findings: Vec<Finding>,
+}
+
+impl<'a> From<&'a Report> for JsonReport<'a> {
+    fn from(report: &'a Report) -> Self {
+        let mut findings = report.findings();
+        findings.append(&mut report.accepted_findings());
+
+        Self {
+            report,
+            findings,
+        }
+    }
+}
+
+// The top level JSON document, with the account level summary after the
+// buckets.
+#[derive(Serialize)]
+pub struct JsonOutput<'a> {
+    buckets: Vec<JsonReport<'a>>,
+    summary: Summary,
+}
+
+impl<'a> JsonOutput<'a> {
+    pub fn new(reports: &'a [Report]) -> Self {
+        let buckets = reports.iter()
+            .filter(|report| report.is_visible_in_json())
+            .map(JsonReport::from)
+            .collect();
+
+        Self {
+            buckets,
+            summary: Summary::new(reports),
+        }
+    }
+}
+
+// The final line of NDJSON output
+#[derive(Serialize)]
+pub struct JsonSummary<'a> {
+    summary: &'a Summary,
+}
+
+impl<'a> From<&'a Summary> for JsonSummary<'a> {
+    fn from(summary: &'a Summary) -> Self {
+        Self {
+            summary,
+        }
+    }
+}
@@ -0,0 +1,298 @@
+// JunitOutput
+// Emits JUnit XML, with a test suite per bucket and a test case per audit.
+use anyhow::Result;
+use crate::common::escape_xml;
+use crate::s3::Finding;
+use super::{
+    visible_reports,
+    Report,
+};
+use std::io::Write;
+
+#[derive(Debug, Eq, PartialEq)]
+struct JunitFailure {
+    kind:    &'static str,
+    message: String,
+}
+
+impl From<&Finding> for JunitFailure {
+    fn from(finding: &Finding) -> Self {
+        Self {
+            kind:    finding.check_id(),
+            message: finding.message().to_string(),
+        }
+    }
+}
+
+// Checks that couldn't be evaluated are reported as errors
+#[derive(Debug)]
+struct JunitTestCase {
+    name:     String,
+    failures: Vec<JunitFailure>,
+    errors:   Vec<JunitFailure>,
+}
+
+#[derive(Debug)]
+struct JunitTestSuite {
+    name:       String,
+    account:    Option<String>,
+    region:     String,
+    test_cases: Vec<JunitTestCase>,
+}
+
+impl JunitTestSuite {
+    fn errors(&self) -> usize {
+        self.test_cases.iter()
+            .filter(|test_case| !test_case.errors.is_empty())
+            .count()
+    }
+
+    fn failures(&self) -> usize {
+        self.test_cases.iter()
+            .filter(|test_case| !test_case.failures.is_empty())
+            .count()
+    }
+
+    fn tests(&self) -> usize {
+        self.test_cases.len()
+    }
+}
+
+#[derive(Debug)]
+pub struct JunitOutput {
+    test_suites: Vec<JunitTestSuite>,
+}
+
+impl From<&Report> for JunitTestSuite {
+    fn from(report: &Report) -> Self {
+        let findings = report.findings();
+        let failures = report.failures();
+
+        // Audits with nothing left to show after filtering are left out
+        let test_cases = report.audits()
+            .into_iter()
+            .filter(|audit| {
+                findings.iter()
+                    .any(|finding| finding.audit() == audit)
+            })
+            .map(|audit| {
+                let failures = failures.iter()
+                    .filter(|finding| *finding.audit() == audit)
+                    .map(JunitFailure::from)
+                    .collect();
+
+                let errors = findings.iter()
+                    .filter(|finding| *finding.audit() == audit)
+                    .filter(|finding| finding.is_error())
+                    .map(JunitFailure::from)
+                    .collect();
+
+                JunitTestCase {
+                    failures,
+                    errors,
+                    name: audit.to_string(),
+                }
+            })
+            .collect();
+
+        Self {
+            test_cases,
+            name:    report.name.to_string(),
+            account: report.account.as_ref().map(ToString::to_string),
+            region:  report.region_name().to_string(),
+        }
+    }
+}
+
+impl From<&[Report]> for JunitOutput {
+    fn from(reports: &[Report]) -> Self {
+        let test_suites = visible_reports(reports)
+            .map(JunitTestSuite::from)
+            .collect();
+
+        Self {
+            test_suites,
+        }
+    }
+}
+
+impl JunitOutput {
+    pub fn write<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        let tests: usize = self.test_suites.iter()
+            .map(JunitTestSuite::tests)
+            .sum();
+
+        let failures: usize = self.test_suites.iter()
+            .map(JunitTestSuite::failures)
+            .sum();
+
+        let errors: usize = self.test_suites.iter()
+            .map(JunitTestSuite::errors)
+            .sum();
+
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            writer,
+            r#"<testsuites name="{}" tests="{}" failures="{}" errors="{}">"#,
+            env!("CARGO_PKG_NAME"),
+            tests,
+            failures,
+            errors,
+        )?;
+
+        for test_suite in &self.test_suites {
+            writeln!(
+                writer,
+                r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}">"#,
+                escape_xml(&test_suite.name),
+                test_suite.tests(),
+                test_suite.failures(),
+                test_suite.errors(),
+            )?;
+            write_properties(writer, test_suite)?;
+
+            for test_case in &test_suite.test_cases {
+                write_test_case(writer, &test_suite.name, test_case)?;
+            }
+
+            writeln!(writer, "  </testsuite>")?;
+        }
+
+        writeln!(writer, "</testsuites>")?;
+
+        Ok(())
+    }
+}
+
+fn write_properties<W>(writer: &mut W, test_suite: &JunitTestSuite) -> Result<()>
+where W: Write,
+{
+    writeln!(writer, "    <properties>")?;
+
+    if let Some(account) = &test_suite.account {
+        writeln!(
+            writer,
+            r#"      <property name="account" value="{}"/>"#,
+            escape_xml(account),
+        )?;
+    }
+
+    writeln!(
+        writer,
+        r#"      <property name="region" value="{}"/>"#,
+        escape_xml(&test_suite.region),
+    )?;
+    writeln!(writer, "    </properties>")?;
+
+    Ok(())
+}
+
+fn write_test_case<W>(
+    writer: &mut W,
+    classname: &str,
+    test_case: &JunitTestCase,
+) -> Result<()>
+where W: Write,
+{
+    let open = format!(
+        r#"    <testcase name="{}" classname="{}""#,
+        escape_xml(&test_case.name),
+        escape_xml(classname),
+    );
+
+    if test_case.failures.is_empty() && test_case.errors.is_empty() {
+        writeln!(writer, "{}/>", open)?;
+        return Ok(());
+    }
+
+    writeln!(writer, "{}>", open)?;
+    write_failures(writer, "failure", &test_case.failures)?;
+    write_failures(writer, "error", &test_case.errors)?;
+    writeln!(writer, "    </testcase>")?;
+
+    Ok(())
+}
+
+// JUnit only allows a single failure or error element per test case, so the
+// first provides the summary and all of them are listed in the body.
+fn write_failures<W>(
+    writer: &mut W,
+    element: &str,
+    failures: &[JunitFailure],
+) -> Result<()>
+where W: Write,
+{
+    let Some(first) = failures.first() else {
+        return Ok(());
+    };
+
+    let body: Vec<String> = failures.iter()
+        .map(|failure| escape_xml(&failure.message))
+        .collect();
+
+    writeln!(
+        writer,
+        r#"      <{} message="{}" type="{}">{}</{}>"#,
+        element,
+        escape_xml(&first.message),
+        escape_xml(first.kind),
+        body.join("\n"),
+        element,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use crate::s3::{
+        Audit,
+        AuditError,
+        BucketAcl,
+        BucketWebsite,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_junit_output() {
+        let reports = vec![
+            Report {
+                acl:     Some(BucketAcl::Public),
+                website: Some(BucketWebsite::Disabled),
+                errors:  vec![
+                    AuditError::new(Audit::Logging, &anyhow!("AccessDenied")),
+                ],
+                ..Report::example()
+            },
+        ];
+
+        let output: JunitOutput = reports.as_slice().into();
+        let mut buffer = Vec::new();
+        output.write(&mut buffer).unwrap();
+
+        let xml = String::from_utf8(buffer).unwrap();
+        let expected = concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#, "\n",
+            r#"<testsuites name="s3audit" tests="3" failures="1" errors="1">"#, "\n",
+            r#"  <testsuite name="s3audit-rs-example-bucket" tests="3" failures="1" errors="1">"#, "\n",
+            r#"    <properties>"#, "\n",
+            r#"      <property name="region" value="eu-west-1"/>"#, "\n",
+            r#"    </properties>"#, "\n",
+            r#"    <testcase name="website" classname="s3audit-rs-example-bucket"/>"#, "\n",
+            r#"    <testcase name="acl" classname="s3audit-rs-example-bucket">"#, "\n",
+            r#"      <failure message="Bucket allows public access via ACL" type="s3audit/acl-public-access">Bucket allows public access via ACL</failure>"#, "\n",
+            r#"    </testcase>"#, "\n",
+            r#"    <testcase name="logging" classname="s3audit-rs-example-bucket">"#, "\n",
+            r#"      <error message="Could not evaluate: AccessDenied" type="s3audit/logging">Could not evaluate: AccessDenied</error>"#, "\n",
+            r#"    </testcase>"#, "\n",
+            r#"  </testsuite>"#, "\n",
+            r#"</testsuites>"#, "\n",
+        );
+
+        assert_eq!(xml, expected);
+    }
+}
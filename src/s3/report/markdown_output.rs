@@ -0,0 +1,236 @@
+// MarkdownOutput
+use anyhow::Result;
+use crate::s3::Finding;
+use std::io::Write;
+use super::{
+    audits_run,
+    visible_reports,
+    Report,
+    Summary,
+};
+
+pub struct MarkdownOutput<'a> {
+    reports: &'a [Report],
+}
+
+impl<'a> MarkdownOutput<'a> {
+    pub fn new(reports: &'a [Report]) -> Self {
+        Self {
+            reports,
+        }
+    }
+
+    pub fn write<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        writeln!(writer, "# S3 Audit Report")?;
+        writeln!(writer)?;
+
+        self.write_summary(writer)?;
+
+        writeln!(writer, "## Buckets")?;
+
+        for report in visible_reports(self.reports) {
+            writeln!(writer)?;
+            write_bucket(writer, report)?;
+        }
+
+        writeln!(writer)?;
+        write_totals(writer, &Summary::new(self.reports))?;
+
+        Ok(())
+    }
+
+    // A table of buckets against audits
+    fn write_summary<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        let audits = audits_run(self.reports);
+
+        writeln!(writer, "## Summary")?;
+        writeln!(writer)?;
+
+        let header: Vec<String> = audits.iter()
+            .map(ToString::to_string)
+            .collect();
+
+        writeln!(writer, "| Bucket | Region | {} |", header.join(" | "))?;
+        writeln!(writer, "|---|---{}|", "|---".repeat(audits.len()))?;
+
+        for report in visible_reports(self.reports) {
+            let cells: Vec<String> = audits.iter()
+                .map(|audit| {
+                    report.audit_status(audit)
+                        .map_or_else(|| "-".into(), |status| status.to_string())
+                })
+                .collect();
+
+            writeln!(
+                writer,
+                "| `{}` | {} | {} |",
+                report.name,
+                report.region_name(),
+                cells.join(" | "),
+            )?;
+        }
+
+        writeln!(writer)?;
+
+        Ok(())
+    }
+}
+
+// A table of the findings for a single bucket
+fn write_bucket<W>(writer: &mut W, report: &Report) -> Result<()>
+where W: Write,
+{
+    writeln!(writer, "### `{}`", report.name)?;
+    writeln!(writer)?;
+    writeln!(writer, "| Status | Severity | Check | Finding | Remediation |")?;
+    writeln!(writer, "|---|---|---|---|---|")?;
+
+    for finding in &report.findings() {
+        write_finding(writer, finding)?;
+    }
+
+    Ok(())
+}
+
+// The account level roll-up
+fn write_totals<W>(writer: &mut W, summary: &Summary) -> Result<()>
+where W: Write,
+{
+    writeln!(writer, "## Totals")?;
+    writeln!(writer)?;
+    writeln!(writer, "- Buckets scanned: {}", summary.buckets)?;
+    writeln!(
+        writer,
+        "- Compliance: {:.1}% ({} of {} buckets have no failures or errors)",
+        summary.compliance,
+        summary.compliant_buckets,
+        summary.buckets,
+    )?;
+    writeln!(
+        writer,
+        "- Buckets with audit errors: {}",
+        summary.errored_buckets,
+    )?;
+
+    if !summary.failures_by_audit.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "| Audit | Failing buckets |")?;
+        writeln!(writer, "|---|---|")?;
+
+        for audit in &summary.failures_by_audit {
+            writeln!(writer, "| {} | {} |", audit.audit, audit.failures)?;
+        }
+    }
+
+    if !summary.most_failures.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "| Bucket | Failures |")?;
+        writeln!(writer, "|---|---|")?;
+
+        for bucket in &summary.most_failures {
+            writeln!(writer, "| `{}` | {} |", bucket.name, bucket.failures)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_finding<W>(writer: &mut W, finding: &Finding) -> Result<()>
+where W: Write,
+{
+    // Remediation is only interesting for things that need fixing
+    let remediation = if finding.is_failure() {
+        escape(finding.remediation())
+    }
+    else {
+        String::new()
+    };
+
+    writeln!(
+        writer,
+        "| {} | {} | `{}` | {} | {} |",
+        finding.status(),
+        finding.severity(),
+        finding.check_id(),
+        escape(finding.message()),
+        remediation,
+    )?;
+
+    Ok(())
+}
+
+// Pipes would end the table cell early
+fn escape(s: &str) -> String {
+    s.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::{
+        BucketAcl,
+        BucketLogging,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_markdown_output() {
+        let reports = vec![
+            Report {
+                acl:     Some(BucketAcl::Public),
+                logging: Some(BucketLogging::Enabled("logs".into())),
+                ..Report::example()
+            },
+        ];
+
+        let output = MarkdownOutput::new(&reports);
+        let mut buffer = Vec::new();
+        output.write(&mut buffer).unwrap();
+
+        let markdown = String::from_utf8(buffer).unwrap();
+        let expected = concat!(
+            "# S3 Audit Report\n",
+            "\n",
+            "## Summary\n",
+            "\n",
+            "| Bucket | Region | acl | logging |\n",
+            "|---|---|---|---|\n",
+            "| `s3audit-rs-example-bucket` | eu-west-1 | fail | pass |\n",
+            "\n",
+            "## Buckets\n",
+            "\n",
+            "### `s3audit-rs-example-bucket`\n",
+            "\n",
+            "| Status | Severity | Check | Finding | Remediation |\n",
+            "|---|---|---|---|---|\n",
+            "| fail | critical | `s3audit/acl-public-access` | Bucket allows public access via ACL | Remove grants to the AllUsers and AuthenticatedUsers groups from the bucket ACL |\n",
+            "| pass | medium | `s3audit/logging` | Logging to logs |  |\n",
+            "\n",
+            "## Totals\n",
+            "\n",
+            "- Buckets scanned: 1\n",
+            "- Compliance: 0.0% (0 of 1 buckets have no failures or errors)\n",
+            "- Buckets with audit errors: 0\n",
+            "\n",
+            "| Audit | Failing buckets |\n",
+            "|---|---|\n",
+            "| acl | 1 |\n",
+            "| logging | 0 |\n",
+            "\n",
+            "| Bucket | Failures |\n",
+            "|---|---|\n",
+            "| `s3audit-rs-example-bucket` | 1 |\n",
+        );
+
+        assert_eq!(markdown, expected);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a|b"), "a\\|b");
+    }
+}
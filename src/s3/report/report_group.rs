@@ -0,0 +1,116 @@
+// Reports split up by the value of a tag, or by account, so that each group
+// can be sent to the team owning those buckets.
+use anyhow::Result;
+use std::path::{
+    Path,
+    PathBuf,
+};
+use super::{
+    open_sink,
+    ReportOptions,
+    Reports,
+};
+
+// Used for buckets without a value to group by
+const UNTAGGED: &str = "untagged";
+
+// The key is the tag name, or "account"
+#[derive(Debug)]
+pub struct ReportGroup {
+    pub key:     String,
+    pub value:   Option<String>,
+    pub reports: Reports,
+}
+
+impl ReportGroup {
+    pub fn new(key: &str, value: Option<String>, reports: Reports) -> Self {
+        Self {
+            value,
+            reports,
+            key: key.into(),
+        }
+    }
+
+    // Shown above the group's text output
+    pub fn heading(&self) -> String {
+        match &self.value {
+            Some(value) => format!("{}={}", self.key, value),
+            None        => format!("{} ({})", self.key, UNTAGGED),
+        }
+    }
+
+    // Each group is written as a complete report, with its own summary.
+    // Outputs with a path are written to a file per group.
+    pub fn output(&self, options: &ReportOptions) -> Result<()> {
+        let heading = self.heading();
+
+        for output in &options.outputs {
+            let path = output.path
+                .as_deref()
+                .map(|path| group_path(path, self.value.as_deref()));
+
+            // Only text output shows the heading
+            let mut sink = open_sink(
+                &output.output_type,
+                path.as_deref(),
+                Some(&heading),
+            )?;
+
+            self.reports.write_to(sink.as_mut())?;
+        }
+
+        Ok(())
+    }
+}
+
+// Adds the tag value to a path, before the extension, so "report.json"
+// becomes "report.team-a.json". Characters that don't belong in file names
+// are replaced.
+fn group_path(path: &Path, value: Option<&str>) -> PathBuf {
+    let value: String = value.unwrap_or(UNTAGGED)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            }
+            else {
+                '_'
+            }
+        })
+        .collect();
+
+    let stem = path.file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+
+    let name = match path.extension() {
+        Some(extension) => {
+            format!("{}.{}.{}", stem, value, extension.to_string_lossy())
+        },
+        None => format!("{}.{}", stem, value),
+    };
+
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_group_path() {
+        let tests = vec![
+            ("report.json",      Some("team-a"),   "report.team-a.json"),
+            ("out/report.json",  Some("team a/b"), "out/report.team_a_b.json"),
+            ("report",           Some("team-a"),   "report.team-a"),
+            ("report.html",      None,             "report.untagged.html"),
+        ];
+
+        for (path, value, expected) in tests {
+            let path = group_path(Path::new(path), value);
+
+            assert_eq!(path, PathBuf::from(expected));
+        }
+    }
+}
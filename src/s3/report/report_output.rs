@@ -0,0 +1,59 @@
+use anyhow::{
+    anyhow,
+    Result,
+};
+use std::path::PathBuf;
+use std::str::FromStr;
+use super::ReportType;
+
+// A report format, and where to write it. Reports without a path are written
+// to stdout.
+#[derive(Clone, Debug, Default)]
+pub struct ReportOutput {
+    pub output_type: ReportType,
+    pub path:        Option<PathBuf>,
+}
+
+// Parses "FORMAT" or "FORMAT=PATH"
+impl FromStr for ReportOutput {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (output_type, path) = match s.split_once('=') {
+            None               => (s, None),
+            Some((_, ""))      => return Err(anyhow!("Empty output path")),
+            Some((kind, path)) => (kind, Some(PathBuf::from(path))),
+        };
+
+        let output_type = output_type.parse()?;
+
+        Ok(Self {
+            output_type,
+            path,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        let output: ReportOutput = "json".parse().unwrap();
+
+        assert!(matches!(output.output_type, ReportType::Json));
+        assert_eq!(output.path, None);
+
+        let output: ReportOutput = "csv=report.csv".parse().unwrap();
+
+        assert!(matches!(output.output_type, ReportType::Csv));
+        assert_eq!(output.path, Some(PathBuf::from("report.csv")));
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        assert!("csv=".parse::<ReportOutput>().is_err());
+        assert!("yaml=report.yaml".parse::<ReportOutput>().is_err());
+    }
+}
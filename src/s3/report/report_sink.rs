@@ -0,0 +1,406 @@
+// Report outputs that are written to as each bucket is audited. Formats that
+// can be written a bucket at a time are flushed after every bucket, so that
+// partial results survive a run that doesn't finish, and only keep what's
+// needed for the summary. Whole document formats keep every report, and are
+// written once every bucket has been audited.
+use anyhow::{
+    Context,
+    Result,
+};
+use colored::Colorize;
+use crate::common::Emoji;
+use std::fs::File;
+use std::io::{
+    self,
+    BufWriter,
+    Write,
+};
+use std::path::Path;
+use super::{
+    JsonSummary,
+    Report,
+    ReportType,
+    Reports,
+    SummaryBuilder,
+};
+
+pub trait ReportSink {
+    // Called before any buckets are written
+    fn begin(&mut self) -> Result<()>;
+
+    // Called with each bucket's report, in the order they're ready
+    fn bucket(&mut self, report: &Report) -> Result<()>;
+
+    // Called once all buckets have been audited
+    fn end(&mut self) -> Result<()>;
+}
+
+// Several outputs are written to together
+impl ReportSink for Vec<Box<dyn ReportSink>> {
+    fn begin(&mut self) -> Result<()> {
+        for sink in self {
+            sink.begin()?;
+        }
+
+        Ok(())
+    }
+
+    fn bucket(&mut self, report: &Report) -> Result<()> {
+        for sink in self {
+            sink.bucket(report)?;
+        }
+
+        Ok(())
+    }
+
+    fn end(&mut self) -> Result<()> {
+        for sink in self {
+            sink.end()?;
+        }
+
+        Ok(())
+    }
+}
+
+// Opens a sink for the given format, writing to stdout or a file. Text
+// output can be given a heading.
+pub fn open_sink(
+    output_type: &ReportType,
+    path: Option<&Path>,
+    heading: Option<&str>,
+) -> Result<Box<dyn ReportSink>> {
+    let writer: Box<dyn Write> = match path {
+        None       => Box::new(io::stdout()),
+        Some(path) => {
+            let file = File::create(path)
+                .with_context(|| {
+                    format!("Could not create {}", path.display())
+                })?;
+
+            Box::new(BufWriter::new(file))
+        },
+    };
+
+    let sink: Box<dyn ReportSink> = match output_type {
+        ReportType::Csv      => Box::new(CsvSink::new(writer)),
+        ReportType::Html     => Box::new(DocumentSink::new(writer, Reports::html)),
+        ReportType::Json     => Box::new(DocumentSink::new(writer, Reports::json)),
+        ReportType::Junit    => Box::new(DocumentSink::new(writer, Reports::junit)),
+        ReportType::Markdown => Box::new(DocumentSink::new(writer, Reports::markdown)),
+        ReportType::Ndjson   => Box::new(NdjsonSink::new(writer)),
+        ReportType::Sarif    => Box::new(DocumentSink::new(writer, Reports::sarif)),
+        ReportType::Text     => Box::new(TextSink::new(writer, heading)),
+    };
+
+    Ok(sink)
+}
+
+// One row per finding, with the header written before the first row
+pub struct CsvSink<W>
+where W: Write,
+{
+    writer: csv::Writer<W>,
+}
+
+impl<W> CsvSink<W>
+where W: Write,
+{
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: csv::Writer::from_writer(writer),
+        }
+    }
+}
+
+impl<W> ReportSink for CsvSink<W>
+where W: Write,
+{
+    fn begin(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn bucket(&mut self, report: &Report) -> Result<()> {
+        report.csv(&mut self.writer)?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+
+    fn end(&mut self) -> Result<()> {
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
+
+// Formats that need every report before anything can be written. Reports
+// are sorted before writing, as they arrive in the order buckets finish.
+pub struct DocumentSink<W>
+where W: Write,
+{
+    writer:  W,
+    write:   fn(&Reports, &mut W) -> Result<()>,
+    reports: Vec<Report>,
+}
+
+impl<W> DocumentSink<W>
+where W: Write,
+{
+    pub fn new(writer: W, write: fn(&Reports, &mut W) -> Result<()>) -> Self {
+        Self {
+            writer,
+            write,
+            reports: Vec::new(),
+        }
+    }
+}
+
+impl<W> ReportSink for DocumentSink<W>
+where W: Write,
+{
+    fn begin(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn bucket(&mut self, report: &Report) -> Result<()> {
+        self.reports.push(report.clone());
+
+        Ok(())
+    }
+
+    fn end(&mut self) -> Result<()> {
+        let mut reports = Reports::new(std::mem::take(&mut self.reports));
+        reports.sort();
+
+        (self.write)(&reports, &mut self.writer)?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
+
+// One JSON object per bucket, one bucket per line, followed by a line holding
+// the summary.
+pub struct NdjsonSink<W>
+where W: Write,
+{
+    writer:  W,
+    summary: SummaryBuilder,
+}
+
+impl<W> NdjsonSink<W>
+where W: Write,
+{
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            summary: SummaryBuilder::default(),
+        }
+    }
+}
+
+impl<W> ReportSink for NdjsonSink<W>
+where W: Write,
+{
+    fn begin(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn bucket(&mut self, report: &Report) -> Result<()> {
+        self.summary.add(report);
+
+        if report.is_visible_in_json() {
+            report.ndjson(&mut self.writer)?;
+            self.writer.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn end(&mut self) -> Result<()> {
+        let summary = self.summary.build();
+        let output  = JsonSummary::from(&summary);
+
+        serde_json::to_writer(&mut self.writer, &output)?;
+        writeln!(self.writer)?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
+
+// The summary follows the buckets, so it's visible at the end of long runs.
+pub struct TextSink<W>
+where W: Write,
+{
+    writer:  W,
+    heading: Option<String>,
+    summary: SummaryBuilder,
+}
+
+impl<W> TextSink<W>
+where W: Write,
+{
+    pub fn new(writer: W, heading: Option<&str>) -> Self {
+        Self {
+            writer,
+            heading: heading.map(ToString::to_string),
+            summary: SummaryBuilder::default(),
+        }
+    }
+}
+
+impl<W> ReportSink for TextSink<W>
+where W: Write,
+{
+    fn begin(&mut self) -> Result<()> {
+        if let Some(heading) = &self.heading {
+            writeln!(self.writer, "{} {}", Emoji::Arrow, heading.bold().magenta())?;
+        }
+
+        Ok(())
+    }
+
+    fn bucket(&mut self, report: &Report) -> Result<()> {
+        self.summary.add(report);
+
+        if report.is_visible() {
+            report.text(&mut self.writer)?;
+            self.writer.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn end(&mut self) -> Result<()> {
+        self.summary.build().text(&mut self.writer)?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::s3::BucketAcl;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ndjson_sink() {
+        let mut sink = NdjsonSink::new(Vec::new());
+
+        let a = Report {
+            name: "bucket-a".into(),
+            acl:  Some(BucketAcl::Private),
+            ..Report::example()
+        };
+
+        sink.begin().unwrap();
+        sink.bucket(&a).unwrap();
+
+        // Each bucket is written as soon as it's given
+        let output = String::from_utf8(sink.writer.clone()).unwrap();
+
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.contains(r#""name":"bucket-a""#));
+
+        sink.end().unwrap();
+
+        // The summary is built from the buckets the sink was given
+        let output = String::from_utf8(sink.writer).unwrap();
+        let last = output.lines().last().unwrap();
+
+        assert_eq!(output.lines().count(), 2);
+        assert!(last.starts_with(r#"{"summary":{"buckets":1,"#));
+    }
+
+    #[test]
+    fn test_csv_sink() {
+        let mut sink = CsvSink::new(Vec::new());
+
+        let a = Report {
+            name: "bucket-a".into(),
+            acl:  Some(BucketAcl::Private),
+            ..Report::example()
+        };
+
+        let b = Report {
+            name: "bucket-b".into(),
+            ..a.clone()
+        };
+
+        sink.begin().unwrap();
+        sink.bucket(&a).unwrap();
+        sink.bucket(&b).unwrap();
+
+        let output = String::from_utf8(sink.writer.get_ref().clone()).unwrap();
+
+        // A single header, followed by a row per finding
+        let headers = output.lines()
+            .filter(|line| line.starts_with("name,"))
+            .count();
+
+        assert_eq!(headers, 1);
+        assert!(output.contains("bucket-a,"));
+        assert!(output.contains("bucket-b,"));
+    }
+
+    #[test]
+    fn test_document_sink() {
+        let mut sink = DocumentSink::new(Vec::new(), Reports::json);
+
+        let a = Report {
+            name: "bucket-a".into(),
+            acl:  Some(BucketAcl::Private),
+            ..Report::example()
+        };
+
+        let b = Report {
+            name: "bucket-b".into(),
+            ..a.clone()
+        };
+
+        sink.begin().unwrap();
+        sink.bucket(&b).unwrap();
+        sink.bucket(&a).unwrap();
+
+        // Nothing is written until every bucket has been audited
+        assert!(sink.writer.is_empty());
+
+        sink.end().unwrap();
+
+        // Buckets are written in order, not in the order they finished
+        let output = String::from_utf8(sink.writer).unwrap();
+        let a = output.find("bucket-a").unwrap();
+        let b = output.find("bucket-b").unwrap();
+
+        assert!(a < b);
+    }
+
+    #[test]
+    fn test_text_sink() {
+        let mut sink = TextSink::new(Vec::new(), None);
+
+        let a = Report {
+            name: "bucket-a".into(),
+            acl:  Some(BucketAcl::Private),
+            ..Report::example()
+        };
+
+        let b = Report {
+            name: "bucket-b".into(),
+            ..a.clone()
+        };
+
+        sink.begin().unwrap();
+        sink.bucket(&a).unwrap();
+        sink.bucket(&b).unwrap();
+        sink.end().unwrap();
+
+        let output = String::from_utf8(sink.writer).unwrap();
+
+        assert!(output.contains("bucket-a"));
+        assert!(output.contains("Buckets scanned: 2"));
+    }
+}
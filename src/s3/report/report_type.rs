@@ -1,18 +1,19 @@
 use anyhow::Result;
 use std::str::FromStr;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub enum ReportType {
     Csv,
+    Html,
+    Json,
+    Junit,
+    Markdown,
+    Ndjson,
+    Sarif,
+    #[default]
     Text,
 }
 
-impl Default for ReportType {
-    fn default() -> Self {
-        Self::Text
-    }
-}
-
 impl FromStr for ReportType {
     type Err = anyhow::Error;
 
@@ -20,9 +21,15 @@ impl FromStr for ReportType {
         let s = s.to_lowercase();
 
         match s.as_str() {
-            "csv"  => Ok(Self::Csv),
-            "text" => Ok(Self::Text),
-            _      => Err(anyhow::anyhow!("Unknown Report Type")),
+            "csv"      => Ok(Self::Csv),
+            "html"     => Ok(Self::Html),
+            "json"     => Ok(Self::Json),
+            "junit"    => Ok(Self::Junit),
+            "markdown" => Ok(Self::Markdown),
+            "ndjson"   => Ok(Self::Ndjson),
+            "sarif"    => Ok(Self::Sarif),
+            "text"     => Ok(Self::Text),
+            _          => Err(anyhow::anyhow!("Unknown Report Type: {}", s)),
         }
     }
 }
@@ -0,0 +1,351 @@
+// SarifOutput
+// Emits failed checks as SARIF 2.1.0 results for code scanning dashboards.
+use crate::s3::{
+    AccountId,
+    Check,
+    Finding,
+    Report,
+    Severity,
+    CHECKS,
+};
+use serde::Serialize;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SarifLevel {
+    Error,
+    Warning,
+    Note,
+}
+
+impl From<Severity> for SarifLevel {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Critical | Severity::High => Self::Error,
+            Severity::Medium                    => Self::Warning,
+            Severity::Low | Severity::Info      => Self::Note,
+        }
+    }
+}
+
+// Numeric severity understood by code scanning dashboards
+fn security_severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "9.0",
+        Severity::High     => "7.0",
+        Severity::Medium   => "5.0",
+        Severity::Low      => "3.0",
+        Severity::Info     => "0.0",
+    }
+}
+
+#[derive(Serialize)]
+pub struct SarifOutput {
+    #[serde(rename = "$schema")]
+    schema:  &'static str,
+    version: &'static str,
+    runs:    Vec<SarifRun>,
+}
+
+#[derive(Serialize)]
+struct SarifRun {
+    tool:        SarifTool,
+    invocations: Vec<SarifInvocation>,
+    results:     Vec<SarifResult>,
+}
+
+// Checks that couldn't be evaluated are reported as notifications
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifInvocation {
+    execution_successful:         bool,
+    tool_execution_notifications: Vec<SarifNotification>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifNotification {
+    level:           SarifLevel,
+    message:         SarifMessage,
+    associated_rule: SarifRuleReference,
+    locations:       Vec<SarifLocation>,
+}
+
+#[derive(Serialize)]
+struct SarifRuleReference {
+    id: &'static str,
+}
+
+#[derive(Serialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifDriver {
+    name:            &'static str,
+    version:         &'static str,
+    information_uri: &'static str,
+    rules:           Vec<SarifRuleDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRuleDescriptor {
+    id:                    &'static str,
+    short_description:     SarifMessage,
+    help:                  SarifMessage,
+    default_configuration: SarifConfiguration,
+    properties:            SarifRuleProperties,
+}
+
+#[derive(Serialize)]
+struct SarifConfiguration {
+    level: SarifLevel,
+}
+
+#[derive(Serialize)]
+struct SarifRuleProperties {
+    #[serde(rename = "security-severity")]
+    security_severity: &'static str,
+}
+
+#[derive(Serialize)]
+struct SarifMessage {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id:   &'static str,
+    level:     SarifLevel,
+    message:   SarifMessage,
+    locations: Vec<SarifLocation>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: SarifPhysicalLocation,
+    logical_locations: Vec<SarifLogicalLocation>,
+    properties:        SarifLocationProperties,
+}
+
+// Bucket ARNs don't include the account or region, so they're given
+// separately
+#[derive(Serialize)]
+struct SarifLocationProperties {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    account: Option<AccountId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region:  Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+}
+
+#[derive(Serialize)]
+struct SarifArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLogicalLocation {
+    name:                 String,
+    fully_qualified_name: String,
+    kind:                 &'static str,
+}
+
+impl From<&Check> for SarifRuleDescriptor {
+    fn from(check: &Check) -> Self {
+        Self {
+            id: check.id,
+            short_description: SarifMessage {
+                text: check.description.into(),
+            },
+            help: SarifMessage {
+                text: check.remediation.into(),
+            },
+            default_configuration: SarifConfiguration {
+                level: check.severity.into(),
+            },
+            properties: SarifRuleProperties {
+                security_severity: security_severity(check.severity),
+            },
+        }
+    }
+}
+
+impl SarifLocation {
+    // Buckets are located by their ARN
+    fn new(report: &Report) -> Self {
+        Self {
+            physical_location: SarifPhysicalLocation {
+                artifact_location: SarifArtifactLocation {
+                    uri: report.arn(),
+                },
+            },
+            logical_locations: vec![
+                SarifLogicalLocation {
+                    name:                 report.name.to_string(),
+                    fully_qualified_name: report.arn(),
+                    kind:                 "resource",
+                },
+            ],
+            properties: SarifLocationProperties {
+                account: report.account.clone(),
+                region:  report.region.clone(),
+            },
+        }
+    }
+}
+
+impl SarifNotification {
+    fn new(report: &Report, finding: &Finding) -> Self {
+        Self {
+            level: SarifLevel::Error,
+            message: SarifMessage {
+                text: finding.message().into(),
+            },
+            associated_rule: SarifRuleReference {
+                id: finding.check_id(),
+            },
+            locations: vec![SarifLocation::new(report)],
+        }
+    }
+}
+
+impl SarifResult {
+    fn new(report: &Report, finding: Finding) -> Self {
+        let location = SarifLocation::new(report);
+
+        Self {
+            rule_id: finding.check_id(),
+            level: finding.severity().into(),
+            message: SarifMessage {
+                text: finding.message().into(),
+            },
+            locations: vec![location],
+        }
+    }
+}
+
+impl From<&[Report]> for SarifOutput {
+    fn from(reports: &[Report]) -> Self {
+        let rules = CHECKS.iter()
+            .map(|&check| check.into())
+            .collect();
+
+        let results = reports.iter()
+            .flat_map(|report| {
+                report.failures()
+                    .into_iter()
+                    .map(move |finding| SarifResult::new(report, finding))
+            })
+            .collect();
+
+        let notifications: Vec<SarifNotification> = reports.iter()
+            .flat_map(|report| {
+                report.findings()
+                    .into_iter()
+                    .filter(Finding::is_error)
+                    .map(move |finding| SarifNotification::new(report, &finding))
+            })
+            .collect();
+
+        let invocation = SarifInvocation {
+            execution_successful:         notifications.is_empty(),
+            tool_execution_notifications: notifications,
+        };
+
+        let driver = SarifDriver {
+            rules,
+            name:            env!("CARGO_PKG_NAME"),
+            version:         env!("CARGO_PKG_VERSION"),
+            information_uri: env!("CARGO_PKG_HOMEPAGE"),
+        };
+
+        let run = SarifRun {
+            results,
+            invocations: vec![invocation],
+            tool: SarifTool {
+                driver,
+            },
+        };
+
+        Self {
+            schema:  SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs:    vec![run],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use crate::s3::{
+        Audit,
+        AuditError,
+        BucketAcl,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_sarif_output_location() {
+        let reports = vec![
+            Report {
+                acl: Some(BucketAcl::Public),
+                ..Report::example()
+            },
+        ];
+
+        let output: SarifOutput = reports.as_slice().into();
+        let output = serde_json::to_value(&output).unwrap();
+
+        let result = &output["runs"][0]["results"][0];
+
+        assert_eq!(output["version"], "2.1.0");
+        assert_eq!(result["ruleId"], "s3audit/acl-public-access");
+        assert_eq!(result["level"], "error");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "arn:aws:s3:::s3audit-rs-example-bucket",
+        );
+        assert_eq!(result["locations"][0]["properties"]["region"], "eu-west-1");
+        assert_eq!(output["runs"][0]["invocations"][0]["executionSuccessful"], true);
+    }
+
+    #[test]
+    fn test_sarif_output_errors() {
+        let reports = vec![
+            Report {
+                acl:    Some(BucketAcl::Public),
+                errors: vec![
+                    AuditError::new(Audit::Logging, &anyhow!("AccessDenied")),
+                ],
+                ..Report::example()
+            },
+        ];
+
+        let output: SarifOutput = reports.as_slice().into();
+        let output = serde_json::to_value(&output).unwrap();
+
+        let invocation = &output["runs"][0]["invocations"][0];
+        let notification = &invocation["toolExecutionNotifications"][0];
+
+        assert_eq!(invocation["executionSuccessful"], false);
+        assert_eq!(notification["associatedRule"]["id"], "s3audit/logging");
+        assert_eq!(notification["message"]["text"], "Could not evaluate: AccessDenied");
+    }
+}
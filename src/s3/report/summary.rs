@@ -0,0 +1,324 @@
+// Account level summary of all reports
+use anyhow::Result;
+use colored::Colorize;
+use crate::common::Emoji;
+use crate::s3::Audit;
+use serde::Serialize;
+use std::io::Write;
+use super::Report;
+
+// How many buckets to list in the most failures section
+const MOST_FAILURES_LIMIT: usize = 10;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct AuditFailures {
+    pub audit:    String,
+    pub failures: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BucketFailures {
+    pub name:     String,
+    pub failures: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Summary {
+    pub buckets:           usize,
+    pub compliant_buckets: usize,
+    pub errored_buckets:   usize,
+    pub compliance:        f64,
+    pub failures_by_audit: Vec<AuditFailures>,
+    pub most_failures:     Vec<BucketFailures>,
+}
+
+impl Summary {
+    pub fn new(reports: &[Report]) -> Self {
+        let mut builder = SummaryBuilder::default();
+
+        for report in reports {
+            builder.add(report);
+        }
+
+        builder.build()
+    }
+
+    // Simple text output
+    pub fn text<W>(&self, writer: &mut W) -> Result<()>
+    where W: Write,
+    {
+        writeln!(writer, "  {} {}", Emoji::Arrow, "Summary".bold().blue())?;
+        writeln!(writer, "    Buckets scanned: {}", self.buckets)?;
+        writeln!(
+            writer,
+            "    Compliance: {:.1}% ({} of {} buckets have no failures or errors)",
+            self.compliance,
+            self.compliant_buckets,
+            self.buckets,
+        )?;
+        writeln!(writer, "    Buckets with audit errors: {}", self.errored_buckets)?;
+
+        if !self.failures_by_audit.is_empty() {
+            writeln!(writer, "    {} Buckets failing each audit", Emoji::Arrow)?;
+
+            for audit in &self.failures_by_audit {
+                writeln!(writer, "      {}: {}", audit.audit, audit.failures)?;
+            }
+        }
+
+        if !self.most_failures.is_empty() {
+            writeln!(
+                writer,
+                "    {} Buckets with the most failures",
+                Emoji::Arrow,
+            )?;
+
+            for bucket in &self.most_failures {
+                writeln!(writer, "      {}: {}", bucket.name, bucket.failures)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Builds a summary a bucket at a time, so that reports don't need to be kept
+// until the end of a run. Only the buckets with the most failures are kept.
+#[derive(Debug, Default)]
+pub struct SummaryBuilder {
+    buckets:           usize,
+    compliant_buckets: usize,
+    errored_buckets:   usize,
+    // In the order each audit was first seen
+    failures_by_audit: Vec<(Audit, usize)>,
+    most_failures:     Vec<BucketFailures>,
+}
+
+impl SummaryBuilder {
+    pub fn add(&mut self, report: &Report) {
+        let failures = report.all_failures();
+
+        self.buckets += 1;
+
+        // Buckets that couldn't be fully audited aren't known to be compliant
+        if report.has_errors() {
+            self.errored_buckets += 1;
+        }
+        else if failures.is_empty() {
+            self.compliant_buckets += 1;
+        }
+
+        // The number of buckets failing each audit
+        for audit in report.audits() {
+            let failed = failures.iter()
+                .any(|finding| *finding.audit() == audit);
+
+            let position = self.failures_by_audit
+                .iter()
+                .position(|(seen, _)| *seen == audit);
+
+            let i = match position {
+                Some(i) => i,
+                None    => {
+                    self.failures_by_audit.push((audit, 0));
+                    self.failures_by_audit.len() - 1
+                },
+            };
+
+            if failed {
+                self.failures_by_audit[i].1 += 1;
+            }
+        }
+
+        if !failures.is_empty() {
+            self.most_failures.push(BucketFailures {
+                name:     report.name.to_string(),
+                failures: failures.len(),
+            });
+
+            self.most_failures.sort_by(|a, b| {
+                b.failures.cmp(&a.failures)
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+
+            self.most_failures.truncate(MOST_FAILURES_LIMIT);
+        }
+    }
+
+    pub fn build(&self) -> Summary {
+        let buckets = self.buckets;
+
+        // An account with no buckets has nothing out of compliance
+        #[allow(clippy::cast_precision_loss)]
+        let compliance = if buckets == 0 {
+            100.0
+        }
+        else {
+            (self.compliant_buckets as f64 / buckets as f64) * 100.0
+        };
+
+        let failures_by_audit = self.failures_by_audit
+            .iter()
+            .map(|(audit, failures)| {
+                AuditFailures {
+                    failures: *failures,
+                    audit:    audit.to_string(),
+                }
+            })
+            .collect();
+
+        Summary {
+            buckets,
+            compliance,
+            compliant_buckets: self.compliant_buckets,
+            errored_buckets:   self.errored_buckets,
+            failures_by_audit,
+            most_failures:     self.most_failures.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use crate::s3::{
+        Audit,
+        AuditError,
+        BucketAcl,
+        BucketLogging,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_summary() {
+        let compliant = Report {
+            acl:     Some(BucketAcl::Private),
+            logging: Some(BucketLogging::Enabled("logs".into())),
+            ..Report::example()
+        };
+
+        let reports = vec![
+            Report {
+                name:    "alpha".into(),
+                logging: Some(BucketLogging::Disabled),
+                ..compliant.clone()
+            },
+            Report {
+                name:    "bravo".into(),
+                acl:     Some(BucketAcl::Public),
+                logging: Some(BucketLogging::Disabled),
+                ..compliant.clone()
+            },
+            Report {
+                name: "charlie".into(),
+                ..compliant.clone()
+            },
+            Report {
+                name: "delta".into(),
+                ..compliant
+            },
+        ];
+
+        let expected = Summary {
+            buckets:           4,
+            compliant_buckets: 2,
+            errored_buckets:   0,
+            compliance:        50.0,
+            failures_by_audit: vec![
+                AuditFailures {
+                    audit:    "acl".into(),
+                    failures: 1,
+                },
+                AuditFailures {
+                    audit:    "logging".into(),
+                    failures: 2,
+                },
+            ],
+            most_failures: vec![
+                BucketFailures {
+                    name:     "bravo".into(),
+                    failures: 2,
+                },
+                BucketFailures {
+                    name:     "alpha".into(),
+                    failures: 1,
+                },
+            ],
+        };
+
+        let summary = Summary::new(&reports);
+
+        assert_eq!(summary, expected);
+    }
+
+    #[test]
+    fn test_summary_errors() {
+        // Every audit failed to run, so nothing is known to be compliant
+        let errored = Report {
+            errors: vec![
+                AuditError::new(Audit::Acl, &anyhow!("AccessDenied")),
+                AuditError::new(Audit::Logging, &anyhow!("AccessDenied")),
+            ],
+            ..Report::new("alpha")
+        };
+
+        let compliant = Report {
+            acl:     Some(BucketAcl::Private),
+            logging: Some(BucketLogging::Enabled("logs".into())),
+            ..Report::new("bravo")
+        };
+
+        let reports = vec![
+            errored,
+            compliant,
+        ];
+
+        let summary = Summary::new(&reports);
+
+        assert_eq!(summary.buckets, 2);
+        assert_eq!(summary.compliant_buckets, 1);
+        assert_eq!(summary.errored_buckets, 1);
+        assert_eq!(summary.compliance, 50.0);
+    }
+
+    #[test]
+    fn test_summary_builder() {
+        let mut builder = SummaryBuilder::default();
+
+        for i in 0..12 {
+            builder.add(&Report {
+                acl:     Some(BucketAcl::Public),
+                logging: Some(BucketLogging::Disabled),
+                ..Report::new(&format!("bucket-{:02}", i))
+            });
+        }
+
+        builder.add(&Report {
+            acl:     Some(BucketAcl::Private),
+            logging: Some(BucketLogging::Enabled("logs".into())),
+            ..Report::new("zulu")
+        });
+
+        // Only the buckets with the most failures are kept as they're added
+        let summary = builder.build();
+        let names: Vec<&str> = summary.most_failures
+            .iter()
+            .map(|bucket| bucket.name.as_str())
+            .collect();
+
+        assert_eq!(summary.buckets, 13);
+        assert_eq!(summary.compliant_buckets, 1);
+        assert_eq!(names.len(), MOST_FAILURES_LIMIT);
+        assert_eq!(names.first(), Some(&"bucket-00"));
+        assert_eq!(names.last(), Some(&"bucket-09"));
+    }
+
+    #[test]
+    fn test_summary_no_buckets() {
+        let summary = Summary::new(&[]);
+
+        assert_eq!(summary.buckets, 0);
+        assert_eq!(summary.compliance, 100.0);
+    }
+}
@@ -0,0 +1,146 @@
+// Pacing and counting of requests, so that large accounts can be audited
+// without being throttled.
+use aws_sdk_s3::config::interceptors::{
+    BeforeSerializationInterceptorContextRef,
+    BeforeTransmitInterceptorContextRef,
+};
+use aws_sdk_s3::config::{
+    ConfigBag,
+    Intercept,
+    RuntimeComponents,
+};
+use aws_sdk_s3::error::BoxError;
+use std::sync::atomic::{
+    AtomicUsize,
+    Ordering,
+};
+use std::sync::{
+    Arc,
+    Mutex,
+};
+use std::time::Duration;
+use tokio::time::Instant;
+
+// Limits S3 API calls to a number per second, shared by every client. It's
+// waited on before each call is sent, so the SDK's retries of a call aren't
+// limited.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    next:     Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / requests_per_second.max(1),
+            next:     Mutex::new(Instant::now()),
+        }
+    }
+
+    // Reserves the next free slot, returning when it starts. Slots are
+    // reserved before waiting, so concurrent requests queue up rather than
+    // all going at once.
+    fn reserve(&self, now: Instant) -> Instant {
+        let mut next = self.next
+            .lock()
+            .expect("rate limiter poisoned");
+
+        let slot = (*next).max(now);
+        *next = slot + self.interval;
+
+        slot
+    }
+
+    // Waits until a request can be made
+    pub async fn wait(&self) {
+        let slot = self.reserve(Instant::now());
+
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+// Counts the attempts made for requests, to find how many were retries. It's
+// cheap to clone, with clones sharing the same counts.
+#[derive(Clone, Debug, Default)]
+pub struct RetryCounter {
+    requests: Arc<AtomicUsize>,
+    attempts: Arc<AtomicUsize>,
+}
+
+impl RetryCounter {
+    // Every attempt after the first for a request is a retry
+    pub fn retries(&self) -> usize {
+        let requests = self.requests.load(Ordering::Relaxed);
+        let attempts = self.attempts.load(Ordering::Relaxed);
+
+        attempts.saturating_sub(requests)
+    }
+}
+
+impl Intercept for RetryCounter {
+    fn name(&self) -> &'static str {
+        "RetryCounter"
+    }
+
+    fn read_before_execution(
+        &self,
+        _context: &BeforeSerializationInterceptorContextRef<'_>,
+        _cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    fn read_before_attempt(
+        &self,
+        _context: &BeforeTransmitInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        _cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        self.attempts.fetch_add(1, Ordering::Relaxed);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_rate_limiter_reserve() {
+        let limiter = RateLimiter::new(4);
+        let now = Instant::now();
+
+        let slots: Vec<Duration> = (0..3)
+            .map(|_| limiter.reserve(now) - now)
+            .collect();
+
+        let expected = vec![
+            Duration::ZERO,
+            Duration::from_millis(250),
+            Duration::from_millis(500),
+        ];
+
+        assert_eq!(slots, expected);
+
+        // Slots aren't saved up while idle
+        let later = now + Duration::from_secs(10);
+
+        assert_eq!(limiter.reserve(later), later);
+    }
+
+    #[test]
+    fn test_retry_counter_retries() {
+        let counter = RetryCounter::default();
+        let clone = counter.clone();
+
+        clone.requests.fetch_add(2, Ordering::Relaxed);
+        clone.attempts.fetch_add(5, Ordering::Relaxed);
+
+        assert_eq!(counter.retries(), 3);
+    }
+}
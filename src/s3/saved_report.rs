@@ -0,0 +1,73 @@
+// Reports saved by a previous run with --format=json
+use anyhow::{
+    Context,
+    Result,
+};
+use crate::s3::Status;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+// Only the parts of a finding needed to compare runs
+#[derive(Debug, Deserialize)]
+pub struct SavedFinding {
+    pub check:   String,
+    pub status:  Status,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SavedBucket {
+    pub name:     String,
+    pub findings: Vec<SavedFinding>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SavedReport {
+    pub buckets: Vec<SavedBucket>,
+}
+
+impl SavedReport {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+
+        serde_json::from_str(&contents)
+            .with_context(|| {
+                format!("Could not parse {} as a JSON report", path.display())
+            })
+    }
+
+    pub fn bucket(&self, name: &str) -> Option<&SavedBucket> {
+        self.buckets.iter()
+            .find(|bucket| bucket.name == name)
+    }
+}
+
+impl SavedBucket {
+    // Returns this bucket's finding matching one from another run of the
+    // bucket. Some checks have a finding for each problem, such as each
+    // policy statement, so findings are matched by their message. A check
+    // with a single finding in both runs is the same finding, even if its
+    // message changed.
+    pub fn finding(&self, other: &Self, finding: &SavedFinding) -> Option<&SavedFinding> {
+        let ours = self.check_findings(&finding.check);
+
+        let matching = ours.iter()
+            .find(|ours| ours.message == finding.message)
+            .copied();
+
+        match ours.as_slice() {
+            [only] if other.check_findings(&finding.check).len() == 1 => {
+                matching.or(Some(only))
+            },
+            _ => matching,
+        }
+    }
+
+    fn check_findings(&self, check: &str) -> Vec<&SavedFinding> {
+        self.findings.iter()
+            .filter(|finding| finding.check == check)
+            .collect()
+    }
+}
@@ -0,0 +1,76 @@
+// Severity of a failed check
+use anyhow::{
+    anyhow,
+    Error,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::fmt;
+use std::str::FromStr;
+
+// Ordered from least to most severe, so that severities can be compared
+// against a threshold.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Info     => "info",
+            Self::Low      => "low",
+            Self::Medium   => "medium",
+            Self::High     => "high",
+            Self::Critical => "critical",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Severity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+
+        match s.as_str() {
+            "info"     => Ok(Self::Info),
+            "low"      => Ok(Self::Low),
+            "medium"   => Ok(Self::Medium),
+            "high"     => Ok(Self::High),
+            "critical" => Ok(Self::Critical),
+            _          => Err(anyhow!("Unknown Severity")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_severity_ordering() {
+        assert!(Severity::Critical > Severity::High);
+        assert!(Severity::High > Severity::Medium);
+        assert!(Severity::Medium > Severity::Low);
+        assert!(Severity::Low > Severity::Info);
+    }
+
+    #[test]
+    fn test_severity_from_str() {
+        let severity: Severity = "HIGH".parse().unwrap();
+
+        assert_eq!(severity, Severity::High);
+        assert!("urgent".parse::<Severity>().is_err());
+    }
+}
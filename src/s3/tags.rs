@@ -0,0 +1,122 @@
+// Bucket tags, used to choose and group the buckets being audited
+use aws_sdk_s3::error::{
+    ProvideErrorMetadata,
+    SdkError,
+};
+use aws_sdk_s3::operation::get_bucket_tagging::{
+    GetBucketTaggingError,
+    GetBucketTaggingOutput,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::ops::Deref;
+
+// Tags are kept sorted by key, so output is stable between runs
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct BucketTags(BTreeMap<String, String>);
+
+impl BucketTags {
+    // Needed by serde, which can't see through Deref
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<GetBucketTaggingOutput> for BucketTags {
+    fn from(output: GetBucketTaggingOutput) -> Self {
+        let tags = output.tag_set()
+            .iter()
+            .map(|tag| (tag.key().to_string(), tag.value().to_string()))
+            .collect();
+
+        Self(tags)
+    }
+}
+
+impl<const N: usize> From<[(&str, &str); N]> for BucketTags {
+    fn from(tags: [(&str, &str); N]) -> Self {
+        let tags = tags.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        Self(tags)
+    }
+}
+
+// Type alias to avoid long lines in the TryFrom impl
+type BucketTaggingResult = Result<
+    GetBucketTaggingOutput,
+    SdkError<GetBucketTaggingError>,
+>;
+
+// A bucket without tags is reported as an error by the API, any other error
+// means we can't tell.
+impl TryFrom<BucketTaggingResult> for BucketTags {
+    type Error = SdkError<GetBucketTaggingError>;
+
+    fn try_from(res: BucketTaggingResult) -> Result<Self, Self::Error> {
+        match res {
+            Ok(output) => Ok(Self::from(output)),
+            Err(error) => {
+                match error.code() {
+                    Some("NoSuchTagSet") => Ok(Self::default()),
+                    _                    => Err(error),
+                }
+            },
+        }
+    }
+}
+
+// Allows us to directly look up tags on the struct inner.
+impl Deref for BucketTags {
+    type Target = BTreeMap<String, String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_s3::types::Tag;
+    use crate::common::service_error;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_from() {
+        let tag = Tag::builder()
+            .key("owner")
+            .value("team-a")
+            .build()
+            .unwrap();
+
+        let output = GetBucketTaggingOutput::builder()
+            .tag_set(tag)
+            .build()
+            .unwrap();
+
+        let tags: BucketTags = output.into();
+
+        assert_eq!(tags, BucketTags::from([("owner", "team-a")]));
+    }
+
+    #[test]
+    fn test_try_from_no_tags() {
+        let error = service_error("NoSuchTagSet", GetBucketTaggingError::generic);
+
+        let tags = BucketTags::try_from(Err(error));
+
+        assert_eq!(tags.unwrap(), BucketTags::default());
+    }
+
+    #[test]
+    fn test_try_from_access_denied() {
+        let error = service_error("AccessDenied", GetBucketTaggingError::generic);
+
+        let tags = BucketTags::try_from(Err(error));
+
+        assert!(tags.is_err());
+    }
+}
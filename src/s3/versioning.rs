@@ -1,13 +1,19 @@
 // Bucket versioning
-use crate::common::Emoji;
+use crate::s3::{
+    Finding,
+    Findings,
+    MFA_DELETE,
+    VERSIONING,
+};
 use aws_sdk_s3::types::{
     BucketVersioningStatus,
     MfaDeleteStatus,
 };
 use aws_sdk_s3::operation::get_bucket_versioning::GetBucketVersioningOutput;
-use std::fmt;
+use serde::Serialize;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum MfaStatus {
     Enabled,
     Disabled,
@@ -23,24 +29,23 @@ impl From<MfaDeleteStatus> for MfaStatus {
     }
 }
 
-impl fmt::Display for MfaStatus {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let output = match self {
+impl Findings for MfaStatus {
+    fn findings(&self) -> Vec<Finding> {
+        let finding = match self {
             Self::Enabled => {
-                let emoji = Emoji::Tick;
-                format!("{} MFA Delete is enabled", emoji)
+                Finding::pass(&MFA_DELETE, "MFA Delete is enabled")
             },
             Self::Disabled => {
-                let emoji = Emoji::Cross;
-                format!("{} MFA Delete is not enabled", emoji)
+                Finding::fail(&MFA_DELETE, "MFA Delete is not enabled")
             },
         };
 
-        write!(f, "{}", output)
+        vec![finding]
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum VersioningStatus {
     Enabled,
     Suspended,
@@ -56,24 +61,22 @@ impl From<BucketVersioningStatus> for VersioningStatus {
     }
 }
 
-impl fmt::Display for VersioningStatus {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let output = match self {
+impl Findings for VersioningStatus {
+    fn findings(&self) -> Vec<Finding> {
+        let finding = match self {
             Self::Enabled => {
-                let emoji = Emoji::Tick;
-                format!("{} Object Versioning is enabled", emoji)
+                Finding::pass(&VERSIONING, "Object Versioning is enabled")
             },
             Self::Suspended => {
-                let emoji = Emoji::Cross;
-                format!("{} Object Versioning is not enabled", emoji)
+                Finding::fail(&VERSIONING, "Object Versioning is not enabled")
             },
         };
 
-        write!(f, "{}", output)
+        vec![finding]
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 pub struct BucketVersioning {
     mfa_delete: MfaStatus,
     versioning: VersioningStatus,
@@ -94,13 +97,12 @@ impl From<GetBucketVersioningOutput> for BucketVersioning {
     }
 }
 
-impl BucketVersioning {
-    pub fn mfa_delete(&self) -> &MfaStatus {
-        &self.mfa_delete
-    }
+impl Findings for BucketVersioning {
+    fn findings(&self) -> Vec<Finding> {
+        let mut findings = self.versioning.findings();
+        findings.append(&mut self.mfa_delete.findings());
 
-    pub fn versioning(&self) -> &VersioningStatus {
-        &self.versioning
+        findings
     }
 }
 
@@ -1,43 +1,95 @@
 // Bucket website
-use crate::common::Emoji;
-use aws_sdk_s3::error::SdkError;
+use crate::s3::{
+    Finding,
+    Findings,
+    WEBSITE,
+};
+use aws_sdk_s3::error::{
+    ProvideErrorMetadata,
+    SdkError,
+};
 use aws_sdk_s3::operation::get_bucket_website::{
     GetBucketWebsiteError,
     GetBucketWebsiteOutput,
 };
-use std::fmt;
+use serde::Serialize;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum BucketWebsite {
     Enabled,
     Disabled,
 }
 
-// Type alias to avoid long line in the From impl
+// Type alias to avoid long line in the TryFrom impl
 type WebsiteResult = Result<GetBucketWebsiteOutput, SdkError<GetBucketWebsiteError>>;
 
-impl From<WebsiteResult> for BucketWebsite {
-    fn from(res: WebsiteResult) -> Self {
+// A missing configuration means website hosting is disabled, any other error
+// means we can't tell.
+impl TryFrom<WebsiteResult> for BucketWebsite {
+    type Error = SdkError<GetBucketWebsiteError>;
+
+    fn try_from(res: WebsiteResult) -> Result<Self, Self::Error> {
         match res {
-            Ok(_)  => Self::Enabled,
-            Err(_) => Self::Disabled,
+            Ok(_)      => Ok(Self::Enabled),
+            Err(error) => {
+                match error.code() {
+                    Some("NoSuchWebsiteConfiguration") => Ok(Self::Disabled),
+                    _                                  => Err(error),
+                }
+            },
         }
     }
 }
 
-impl fmt::Display for BucketWebsite {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let output = match self {
+impl Findings for BucketWebsite {
+    fn findings(&self) -> Vec<Finding> {
+        let finding = match self {
             Self::Enabled => {
-                let emoji = Emoji::Warning;
-                format!("{} Static website hosting is enabled", emoji)
+                Finding::warn(&WEBSITE, "Static website hosting is enabled")
             },
             Self::Disabled => {
-                let emoji = Emoji::Tick;
-                format!("{} Static website hosting is disabled", emoji)
+                Finding::pass(&WEBSITE, "Static website hosting is disabled")
             },
         };
 
-        write!(f, "{}", output)
+        vec![finding]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::service_error;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_try_from_no_website() {
+        let error = service_error(
+            "NoSuchWebsiteConfiguration",
+            GetBucketWebsiteError::generic,
+        );
+
+        let website = BucketWebsite::try_from(Err(error));
+
+        assert_eq!(website.unwrap(), BucketWebsite::Disabled);
+    }
+
+    #[test]
+    fn test_try_from_access_denied() {
+        let error = service_error("AccessDenied", GetBucketWebsiteError::generic);
+
+        let website = BucketWebsite::try_from(Err(error));
+
+        assert!(website.is_err());
+    }
+
+    #[test]
+    fn test_try_from_website() {
+        let output = GetBucketWebsiteOutput::builder().build();
+
+        let website = BucketWebsite::try_from(Ok(output));
+
+        assert_eq!(website.unwrap(), BucketWebsite::Enabled);
     }
 }